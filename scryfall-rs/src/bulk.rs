@@ -9,28 +9,42 @@
 //!
 //! # Features
 //!
-//! With the `bulk_caching` feature enabled, bulk data files will be stored in
-//! the OS temp folder. This prevents duplicate downloads if the version has
-//! already been saved.
+//! With the `bulk_caching` feature enabled, bulk data files will be stored
+//! gzip-compressed in a [`cache`] folder, the OS temp folder by default. This
+//! prevents duplicate downloads if the version has already been saved.
+//!
+//! With the `async` feature enabled, bulk data files can be downloaded and
+//! streamed without blocking the thread, see
+//! `BulkDataFile::load_stream_async`.
 //!
 //! See also: [Official Docs](https://scryfall.com/docs/api/bulk-data)
 
-use std::fs::File;
+#[cfg(feature = "async")]
+use std::collections::VecDeque;
+use std::fs::{self, File};
 use std::io;
-use std::io::BufReader;
-use std::path::Path;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
 
 use cfg_if::cfg_if;
 use chrono::{DateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+#[cfg(feature = "async")]
+use futures_util::stream::{self, Stream};
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::card::Card;
+use crate::error::Error;
 use crate::ruling::Ruling;
 use crate::uri::Uri;
 use crate::util::array_stream_reader::ArrayStreamReader;
-use crate::util::BULK_DATA_URL;
+use crate::util::bulk_data_url;
+
+#[cfg(feature = "bulk_caching")]
+pub mod cache;
 
 /// Scryfall provides daily exports of our card data in bulk files. Each of
 /// these files is represented as a bulk_data object via the API. URLs for files
@@ -81,6 +95,11 @@ pub struct BulkDataFile<T> {
     /// The size of this file in integer bytes.
     pub compressed_size: usize,
 
+    /// The size of this file uncompressed in integer bytes, if Scryfall lists
+    /// it.
+    #[serde(default)]
+    pub size: Option<usize>,
+
     /// The MIME type of this file.
     pub content_type: String,
 
@@ -92,23 +111,25 @@ pub struct BulkDataFile<T> {
 impl<T: DeserializeOwned> BulkDataFile<T> {
     cfg_if! {
         if #[cfg(feature = "bulk_caching")] {
-            /// The full temp path where this file will be downloaded with `load`. The
-            /// file name has the form "&lt;type&gt;-&lt;date&gt;.json".
-            fn cache_path(&self) -> std::path::PathBuf {
-                use heck::KebabCase;
-                std::env::temp_dir().join(format!(
-                    "{}-{}.json",
-                    self.bulk_type.to_kebab_case(),
-                    self.updated_at.format("%Y%m%d%H%M%S"),
-                ))
+            /// The full path in the [current cache][cache::BulkCache::current]
+            /// where this file will be downloaded with `load`. The file name has
+            /// the form "&lt;type&gt;-&lt;date&gt;.json.gz", and the file is
+            /// stored gzip-compressed.
+            pub fn cache_path(&self) -> PathBuf {
+                cache::BulkCache::current().path(&self.bulk_type, self.updated_at)
             }
 
-            fn get_reader(&self) -> crate::Result<BufReader<File>> {
-                let cache_path = self.cache_path();
+            fn get_reader(&self) -> crate::Result<BufReader<flate2::read::GzDecoder<File>>> {
+                let cache = cache::BulkCache::current();
+                let cache_path = cache.path(&self.bulk_type, self.updated_at);
                 if !cache_path.exists() {
+                    fs::create_dir_all(cache.dir())?;
                     self.download(&cache_path)?;
+                    // Old versions that can't be removed yet are retried on
+                    // the next download.
+                    cache.evict().ok();
                 }
-                Ok(BufReader::new(File::open(cache_path)?))
+                Ok(BufReader::new(flate2::read::GzDecoder::new(File::open(cache_path)?)))
             }
         } else {
             fn get_reader(&self) -> crate::Result<BufReader<impl io::Read + Send>> {
@@ -119,12 +140,12 @@ impl<T: DeserializeOwned> BulkDataFile<T> {
 
     /// Gets a BulkDataFile of the specified type.
     pub fn of_type(bulk_type: &str) -> crate::Result<Self> {
-        Uri::from(BULK_DATA_URL.join(bulk_type)?).fetch()
+        Uri::from(bulk_data_url().join(bulk_type)?).fetch()
     }
 
     /// Gets a BulkDataFile with the specified unique ID.
     pub fn id(id: Uuid) -> crate::Result<Self> {
-        Uri::from(BULK_DATA_URL.join(id.to_string().as_str())?).fetch()
+        Uri::from(bulk_data_url().join(id.to_string().as_str())?).fetch()
     }
 
     /// Loads the objects from this bulk data download into a `Vec`.
@@ -148,12 +169,307 @@ impl<T: DeserializeOwned> BulkDataFile<T> {
 
     /// Downloads this file, saving it to `path`. Overwrites the file if it
     /// already exists.
+    ///
+    /// The file is downloaded next to `path` and only moved there once it is
+    /// complete, so `path` never holds a partial download. Fails if the
+    /// download is shorter or longer than the server said it would be.
+    ///
+    /// If `path` ends in `.gz`, the file is stored gzip-compressed.
     pub fn download(&self, path: impl AsRef<Path>) -> crate::Result<()> {
+        self.download_with_progress(path, |_, _| {})
+    }
+
+    /// Downloads this file like [`download`][Self::download], calling
+    /// `progress` with the number of bytes written so far and the total
+    /// number of bytes, if the server sent it, after every chunk.
+    pub fn download_with_progress(
+        &self,
+        path: impl AsRef<Path>,
+        mut progress: impl FnMut(u64, Option<u64>),
+    ) -> crate::Result<()> {
         let path = path.as_ref();
         let response = self.download_uri.fetch_raw()?;
-        io::copy(&mut response.into_reader(), &mut File::create(path)?)?;
+        let total = response
+            .header("Content-Length")
+            .and_then(|length| length.parse().ok());
+        // ureq decompresses gzip bodies and drops their Content-Length, so
+        // they're checked against the uncompressed size.
+        let expected = self.expected_size(total, false);
+        let mut reader = response.into_reader();
+        let partial = partial_path(path);
+        let written = (|| {
+            // The body has already been decompressed if it was sent
+            // compressed.
+            let mut file = DownloadFile::create(&partial, is_gzip(path))?;
+            let mut buffer = [0; 64 * 1024];
+            let mut written = 0;
+            loop {
+                let read = match reader.read(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(read) => read,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e.into()),
+                };
+                file.write_all(&buffer[..read])?;
+                written += read as u64;
+                progress(written, total);
+            }
+            file.finish()?;
+            Ok(written)
+        })();
+        complete_download(&partial, path, written, expected)
+    }
+
+    /// The size a download should have: its `Content-Length`, or else the
+    /// size Scryfall lists for the file, compressed if it arrives compressed.
+    fn expected_size(&self, content_length: Option<u64>, compressed: bool) -> Option<u64> {
+        content_length.or(if compressed {
+            Some(self.compressed_size as u64)
+        } else {
+            self.size.map(|size| size as u64)
+        })
+    }
+}
+
+/// Whether a file is stored gzip-compressed, judging by its extension.
+fn is_gzip(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension == "gz")
+}
+
+/// A file a download is written to, compressed unless it's stored the way it
+/// arrives.
+enum DownloadFile {
+    Plain(File),
+    Gzip(GzEncoder<File>),
+}
+
+impl DownloadFile {
+    fn create(path: &Path, compress: bool) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(if compress {
+            DownloadFile::Gzip(GzEncoder::new(file, Compression::default()))
+        } else {
+            DownloadFile::Plain(file)
+        })
+    }
+
+    /// Finishes compressing and makes sure the file is on disk.
+    fn finish(self) -> io::Result<()> {
+        let file = match self {
+            DownloadFile::Plain(file) => file,
+            DownloadFile::Gzip(encoder) => encoder.finish()?,
+        };
+        file.sync_all()
+    }
+}
+
+impl Write for DownloadFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            DownloadFile::Plain(file) => file.write(buf),
+            DownloadFile::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            DownloadFile::Plain(file) => file.flush(),
+            DownloadFile::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// The file a download to `path` is written to until it is complete, next to
+/// it so it can be renamed.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// Moves a finished download from `partial` to `path` if it has the expected
+/// size, and removes it otherwise.
+fn complete_download(
+    partial: &Path,
+    path: &Path,
+    written: crate::Result<u64>,
+    expected: Option<u64>,
+) -> crate::Result<()> {
+    let verified = written.and_then(|written| match expected {
+        Some(expected) if written != expected => Err(Error::Other(format!(
+            "Downloaded {} bytes of {}, expected {}",
+            written,
+            path.display(),
+            expected
+        ))),
+        _ => Ok(()),
+    });
+    match verified {
+        Ok(()) => Ok(fs::rename(partial, path)?),
+        Err(e) => {
+            fs::remove_file(partial).ok();
+            Err(e)
+        },
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: DeserializeOwned> BulkDataFile<T> {
+    /// Gets a BulkDataFile of the specified type without blocking the thread.
+    pub async fn of_type_async(bulk_type: &str) -> crate::Result<Self> {
+        Uri::from(bulk_data_url().join(bulk_type)?)
+            .fetch_async()
+            .await
+    }
+
+    /// Gets a BulkDataFile with the specified unique ID without blocking the
+    /// thread.
+    pub async fn id_async(id: Uuid) -> crate::Result<Self> {
+        Uri::from(bulk_data_url().join(id.to_string().as_str())?)
+            .fetch_async()
+            .await
+    }
+
+    /// Streams the objects from this bulk data download while it is being
+    /// downloaded, only keeping the part of the file in memory that hasn't
+    /// been deserialized yet. The file is not cached, use
+    /// [`download_async`][Self::download_async] and
+    /// [`load_iter`][Self::load_iter] for that.
+    pub async fn load_stream_async(&self) -> crate::Result<impl Stream<Item = crate::Result<T>>> {
+        let response = self.download_uri.fetch_raw_async().await?;
+        let state = Some((response, self.download_uri.clone(), StreamDecoder::new()));
+        Ok(stream::unfold(state, |state| async move {
+            let (mut response, uri, mut decoder) = state?;
+            loop {
+                if let Some(item) = decoder.items.pop_front() {
+                    return Some((Ok(item), Some((response, uri, decoder))));
+                }
+                let decoded = match response.chunk().await {
+                    Ok(Some(chunk)) => decoder.push(&chunk),
+                    Ok(None) => match decoder.finish() {
+                        Ok(()) if decoder.items.is_empty() => return None,
+                        result => result,
+                    },
+                    Err(e) => Err(uri.reqwest_error(e)),
+                };
+                if let Err(e) = decoded {
+                    return Some((Err(e), None));
+                }
+            }
+        }))
+    }
+
+    /// Downloads this file like [`download`][Self::download], awaiting the
+    /// response instead of blocking the thread.
+    pub async fn download_async(&self, path: impl AsRef<Path>) -> crate::Result<()> {
+        self.download_async_with_progress(path, |_, _| {}).await
+    }
+
+    /// Downloads this file like
+    /// [`download_with_progress`][Self::download_with_progress], awaiting
+    /// every chunk instead of blocking the thread.
+    ///
+    /// If `path` ends in `.gz`, the file is requested gzip-compressed and
+    /// stored as it arrives, or compressed while it's written if the server
+    /// sends it uncompressed.
+    pub async fn download_async_with_progress(
+        &self,
+        path: impl AsRef<Path>,
+        mut progress: impl FnMut(u64, Option<u64>),
+    ) -> crate::Result<()> {
+        let path = path.as_ref();
+        let gzip = is_gzip(path);
+        let mut response = if gzip {
+            self.download_uri.fetch_gzip_async().await?
+        } else {
+            self.download_uri.fetch_raw_async().await?
+        };
+        let total = response.content_length();
+        let content_encoding = response
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|encoding| encoding.to_str().ok());
+        let compressed = content_encoding == Some(self.content_encoding.as_str());
+        let expected = self.expected_size(total, compressed);
+        let compress = gzip && content_encoding != Some("gzip");
+        let partial = partial_path(path);
+        let written: crate::Result<u64> = async {
+            let mut file = DownloadFile::create(&partial, compress)?;
+            let mut written = 0;
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| self.download_uri.reqwest_error(e))?
+            {
+                file.write_all(&chunk)?;
+                written += chunk.len() as u64;
+                progress(written, total);
+            }
+            file.finish()?;
+            Ok(written)
+        }
+        .await;
+        complete_download(&partial, path, written, expected)
+    }
+}
+
+/// Deserializes the items of a JSON array that arrives in chunks.
+#[cfg(feature = "async")]
+struct StreamDecoder<T> {
+    reader: ArrayStreamReader<VecDeque<u8>>,
+    json: Vec<u8>,
+    items: VecDeque<T>,
+}
+
+#[cfg(feature = "async")]
+impl<T: DeserializeOwned> StreamDecoder<T> {
+    fn new() -> Self {
+        StreamDecoder {
+            reader: ArrayStreamReader::new(VecDeque::new()),
+            json: vec![],
+            items: VecDeque::new(),
+        }
+    }
+
+    /// Deserializes all items that are complete after adding `chunk`.
+    fn push(&mut self, chunk: &[u8]) -> crate::Result<()> {
+        self.reader.get_mut().extend(chunk);
+        let mut buffer = [0; 64 * 1024];
+        loop {
+            match self.reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => self.json.extend_from_slice(&buffer[..read]),
+                // The chunk ended inside the array, wait for the next one.
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let mut items = serde_json::Deserializer::from_slice(&self.json).into_iter();
+        loop {
+            match items.next() {
+                Some(Ok(item)) => self.items.push_back(item),
+                Some(Err(e)) if e.is_eof() => break,
+                Some(Err(e)) => return Err(e.into()),
+                None => break,
+            }
+        }
+        let decoded = items.byte_offset();
+        self.json.drain(..decoded);
         Ok(())
     }
+
+    /// Checks that the array was complete once the download has ended.
+    fn finish(&mut self) -> crate::Result<()> {
+        // Fails if the outer array wasn't closed.
+        let trailing = self.reader.read(&mut [0])?;
+        if trailing == 0 && self.json.iter().all(u8::is_ascii_whitespace) {
+            Ok(())
+        } else {
+            Err(io::Error::from(io::ErrorKind::UnexpectedEof).into())
+        }
+    }
 }
 
 /// An iterator containing one Scryfall card object for each Oracle ID on
@@ -189,6 +505,57 @@ pub fn rulings() -> crate::Result<impl Iterator<Item = crate::Result<Ruling>>> {
     BulkDataFile::of_type("rulings")?.load_iter()
 }
 
+/// Streams one Scryfall card object for each Oracle ID on Scryfall like
+/// [`oracle_cards`], without blocking the thread.
+#[cfg(feature = "async")]
+pub async fn oracle_cards_async() -> crate::Result<impl Stream<Item = crate::Result<Card>>> {
+    BulkDataFile::of_type_async("oracle_cards")
+        .await?
+        .load_stream_async()
+        .await
+}
+
+/// Streams Scryfall card objects that together contain all unique artworks
+/// like [`unique_artwork`], without blocking the thread.
+#[cfg(feature = "async")]
+pub async fn unique_artwork_async() -> crate::Result<impl Stream<Item = crate::Result<Card>>> {
+    BulkDataFile::of_type_async("unique_artwork")
+        .await?
+        .load_stream_async()
+        .await
+}
+
+/// Streams every card object on Scryfall in English or the printed language
+/// like [`default_cards`], without blocking the thread.
+#[cfg(feature = "async")]
+pub async fn default_cards_async() -> crate::Result<impl Stream<Item = crate::Result<Card>>> {
+    BulkDataFile::of_type_async("default_cards")
+        .await?
+        .load_stream_async()
+        .await
+}
+
+/// Streams every card object on Scryfall in every language like
+/// [`all_cards`], without blocking the thread. Unlike the iterator, the stream
+/// doesn't hold the whole file in memory.
+#[cfg(feature = "async")]
+pub async fn all_cards_async() -> crate::Result<impl Stream<Item = crate::Result<Card>>> {
+    BulkDataFile::of_type_async("all_cards")
+        .await?
+        .load_stream_async()
+        .await
+}
+
+/// Streams all Rulings on Scryfall like [`rulings`], without blocking the
+/// thread.
+#[cfg(feature = "async")]
+pub async fn rulings_async() -> crate::Result<impl Stream<Item = crate::Result<Ruling>>> {
+    BulkDataFile::of_type_async("rulings")
+        .await?
+        .load_stream_async()
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -231,6 +598,132 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    #[ignore]
+    async fn oracle_cards_async() {
+        use futures_util::{pin_mut, StreamExt};
+
+        let cards = super::oracle_cards_async()
+            .await
+            .expect("Couldn't get the bulk object");
+        pin_mut!(cards);
+        while let Some(card) = cards.next().await {
+            card.unwrap();
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn decode_chunked_array() {
+        let json = br#"[{"a": "x,]}"}, {"a": "\"y"},{"a":"z"}]"#;
+        for chunk_size in 1..json.len() {
+            let mut decoder = super::StreamDecoder::<serde_json::Value>::new();
+            for chunk in json.chunks(chunk_size) {
+                decoder.push(chunk).unwrap();
+            }
+            decoder.finish().unwrap();
+            let values: Vec<_> = decoder.items.iter().map(|item| &item["a"]).collect();
+            assert_eq!(values, ["x,]}", "\"y", "z"]);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn decode_truncated_array() {
+        let mut decoder = super::StreamDecoder::<serde_json::Value>::new();
+        decoder.push(br#"[{"a": 1}, {"a""#).unwrap();
+        assert_eq!(decoder.items.len(), 1);
+        assert!(decoder.finish().is_err());
+    }
+
+    #[test]
+    fn download_completely_or_not_at_all() {
+        use std::fs;
+
+        use super::{complete_download, partial_path, BulkDataFile};
+        use crate::util::mock_server::mock_server;
+
+        let (address, server) = mock_server(vec![("200 OK", "", "[1, 2, 3]")]);
+        let file: BulkDataFile<u32> = serde_json::from_value(serde_json::json!({
+            "id": "00000000-0000-0000-0000-000000000001",
+            "uri": address,
+            "type": "numbers",
+            "name": "Numbers",
+            "description": "Some numbers",
+            "download_uri": address,
+            "updated_at": "2021-01-01T10:00:00.000+00:00",
+            "compressed_size": 5,
+            "size": 9,
+            "content_type": "application/json",
+            "content_encoding": "gzip"
+        }))
+        .unwrap();
+        assert_eq!(file.expected_size(Some(7), false), Some(7));
+        assert_eq!(file.expected_size(None, false), Some(9));
+        assert_eq!(file.expected_size(None, true), Some(5));
+        let dir =
+            std::env::temp_dir().join(format!("scryfall-download-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("numbers.json");
+        let partial = partial_path(&path);
+
+        file.download(&path).unwrap();
+        server.join().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[1, 2, 3]");
+        assert!(!partial.exists());
+
+        fs::write(&partial, "[1, ").unwrap();
+        assert!(complete_download(&partial, &path, Ok(4), Some(9)).is_err());
+        assert!(!partial.exists());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[1, 2, 3]");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "bulk_caching")]
+    #[test]
+    fn load_from_compressed_cache() {
+        use std::fs;
+        use std::io::Read;
+
+        use super::cache::BulkCache;
+        use super::BulkDataFile;
+        use crate::util::mock_server::mock_server;
+
+        // Only one response, so loading again must use the cached file.
+        let (address, server) = mock_server(vec![("200 OK", "", "[1, 2, 3]")]);
+        let file: BulkDataFile<u32> = serde_json::from_value(serde_json::json!({
+            "id": "00000000-0000-0000-0000-000000000002",
+            "uri": address,
+            "type": "cached_numbers",
+            "name": "Cached numbers",
+            "description": "Some numbers",
+            "download_uri": address,
+            "updated_at": "2021-01-01T10:00:00.000+00:00",
+            "compressed_size": 9,
+            "content_type": "application/json",
+            "content_encoding": "gzip"
+        }))
+        .unwrap();
+        let dir =
+            std::env::temp_dir().join(format!("scryfall-bulk-cache-test-{}", std::process::id()));
+        BulkCache::new(&dir).set_default();
+
+        let numbers: Vec<_> = file.load_iter().unwrap().map(Result::unwrap).collect();
+        assert_eq!(numbers, [1, 2, 3]);
+        server.join().unwrap();
+        assert_eq!(file.load().unwrap(), [1, 2, 3]);
+
+        let cache_path = file.cache_path();
+        assert!(cache_path.ends_with("cached-numbers-20210101100000.json.gz"));
+        let mut json = String::new();
+        flate2::read::GzDecoder::new(fs::File::open(&cache_path).unwrap())
+            .read_to_string(&mut json)
+            .unwrap();
+        assert_eq!(json, "[1, 2, 3]");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_parse_list() {
         use serde_json::Deserializer;
@@ -0,0 +1,274 @@
+//! The folder bulk data files are cached in by
+//! [`load`][super::BulkDataFile::load] and
+//! [`load_iter`][super::BulkDataFile::load_iter].
+//!
+//! Cached files are gzip-compressed and named `<type>-<date>.json.gz` after
+//! the bulk type and the time it was updated, so a new version of a file is
+//! downloaded next to the old ones. Only the [`keep`][BulkCache::keep] latest
+//! versions of every type are kept once a new one is downloaded.
+//!
+//! # Examples
+//! ```rust,no_run
+//! use scryfall::bulk::cache::BulkCache;
+//!
+//! BulkCache::new("/var/cache/scryfall").keep(1).set_default();
+//! for file in BulkCache::current().list()? {
+//!     println!("{} from {}: {} bytes", file.bulk_type, file.updated_at, file.size);
+//! }
+//! # Ok::<(), scryfall::Error>(())
+//! ```
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use heck::KebabCase;
+use once_cell::sync::Lazy;
+
+static DEFAULT_CACHE: Lazy<RwLock<BulkCache>> = Lazy::new(|| RwLock::new(BulkCache::default()));
+
+/// The format of the update time in the name of a cached file.
+const DATE_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// A folder bulk data files are cached in, and how many versions of each file
+/// to keep.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BulkCache {
+    dir: PathBuf,
+    keep: usize,
+}
+
+/// A bulk data file in a [`BulkCache`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CachedBulkFile {
+    /// The type of the file, in kebab case like `oracle-cards`.
+    pub bulk_type: String,
+    /// When Scryfall updated this version of the file.
+    pub updated_at: DateTime<Utc>,
+    /// Where the file is.
+    pub path: PathBuf,
+    /// The size of the file in bytes.
+    pub size: u64,
+}
+
+impl Default for BulkCache {
+    fn default() -> Self {
+        BulkCache::new(std::env::temp_dir())
+    }
+}
+
+impl BulkCache {
+    /// The number of versions of each type kept unless configured otherwise,
+    /// so a file that's still being read isn't removed when a newer one is
+    /// downloaded.
+    pub const DEFAULT_KEEP: usize = 2;
+
+    /// A cache in `dir`, keeping [`DEFAULT_KEEP`][Self::DEFAULT_KEEP]
+    /// versions of each file. The folder is created when a file is cached.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        BulkCache {
+            dir: dir.into(),
+            keep: Self::DEFAULT_KEEP,
+        }
+    }
+
+    /// Sets the number of versions of each type to keep, at least 1.
+    pub fn keep(mut self, keep: usize) -> Self {
+        self.keep = keep.max(1);
+        self
+    }
+
+    /// The cache used by [`load`][super::BulkDataFile::load] and
+    /// [`load_iter`][super::BulkDataFile::load_iter], the OS temp folder by
+    /// default.
+    pub fn current() -> BulkCache {
+        DEFAULT_CACHE
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Replaces the cache used by [`load`][super::BulkDataFile::load] and
+    /// [`load_iter`][super::BulkDataFile::load_iter].
+    pub fn set_default(self) {
+        *DEFAULT_CACHE.write().unwrap_or_else(|e| e.into_inner()) = self;
+    }
+
+    /// The folder the files are cached in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The path a version of a bulk data file is cached at.
+    pub(crate) fn path(&self, bulk_type: &str, updated_at: DateTime<Utc>) -> PathBuf {
+        self.dir.join(format!(
+            "{}-{}.json.gz",
+            bulk_type.to_kebab_case(),
+            updated_at.format(DATE_FORMAT),
+        ))
+    }
+
+    /// Lists the cached files, ordered by type and then newest first. Files
+    /// cached uncompressed by older versions of this crate are included,
+    /// other files in the folder are ignored, and so is a missing folder.
+    pub fn list(&self) -> crate::Result<Vec<CachedBulkFile>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut files = vec![];
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name();
+            let (bulk_type, updated_at) = match name.to_str().and_then(parse_file_name) {
+                Some(parsed) => parsed,
+                None => continue,
+            };
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                files.push(CachedBulkFile {
+                    bulk_type,
+                    updated_at,
+                    path: entry.path(),
+                    size: metadata.len(),
+                });
+            }
+        }
+        files.sort_by(|a, b| {
+            a.bulk_type
+                .cmp(&b.bulk_type)
+                .then(b.updated_at.cmp(&a.updated_at))
+        });
+        Ok(files)
+    }
+
+    /// Removes all but the latest [`keep`][Self::keep] versions of each type
+    /// and returns the removed files.
+    pub fn evict(&self) -> crate::Result<Vec<CachedBulkFile>> {
+        let mut kept = 0;
+        let mut previous_type = None;
+        let mut removed = vec![];
+        for file in self.list()? {
+            if previous_type.as_ref() != Some(&file.bulk_type) {
+                previous_type = Some(file.bulk_type.clone());
+                kept = 0;
+            }
+            if kept < self.keep {
+                kept += 1;
+            } else {
+                fs::remove_file(&file.path)?;
+                removed.push(file);
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Removes all cached files and returns them. Partial downloads left by
+    /// interrupted ones are removed as well.
+    pub fn purge(&self) -> crate::Result<Vec<CachedBulkFile>> {
+        let files = self.list()?;
+        for file in &files {
+            fs::remove_file(&file.path)?;
+        }
+        for partial in self.partial_downloads()? {
+            fs::remove_file(partial)?;
+        }
+        Ok(files)
+    }
+
+    /// The `.part` files of cached files that are still being downloaded or
+    /// whose download was interrupted.
+    fn partial_downloads(&self) -> crate::Result<Vec<PathBuf>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut partials = vec![];
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name();
+            let is_partial = name
+                .to_str()
+                .and_then(|name| name.strip_suffix(".part"))
+                .and_then(parse_file_name)
+                .is_some();
+            if is_partial && entry.metadata()?.is_file() {
+                partials.push(entry.path());
+            }
+        }
+        Ok(partials)
+    }
+}
+
+/// Parses the type and update time out of a name like
+/// `oracle-cards-20210101100000.json.gz`, or `.json` if it is uncompressed.
+fn parse_file_name(name: &str) -> Option<(String, DateTime<Utc>)> {
+    let stem = name.strip_suffix(".gz").unwrap_or(name);
+    let (bulk_type, date) = stem.strip_suffix(".json")?.rsplit_once('-')?;
+    let valid_type = !bulk_type.is_empty()
+        && bulk_type
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid_type || date.len() != 14 {
+        return None;
+    }
+    let updated_at = NaiveDateTime::parse_from_str(date, DATE_FORMAT).ok()?;
+    Some((bulk_type.to_string(), DateTime::from_utc(updated_at, Utc)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn list_evict_and_purge() {
+        let dir = std::env::temp_dir().join(format!("scryfall-cache-test-{}", std::process::id()));
+        let cache = BulkCache::new(&dir).keep(2);
+        assert!(cache.list().unwrap().is_empty());
+
+        fs::create_dir_all(&dir).unwrap();
+        let day = |day| Utc.ymd(2021, 1, day).and_hms(10, 0, 0);
+        for (bulk_type, days) in [("oracle_cards", 1..4), ("rulings", 1..2)] {
+            for day in days.map(day) {
+                fs::write(cache.path(bulk_type, day), "[]").unwrap();
+            }
+        }
+        fs::write(dir.join("notes.json"), "{}").unwrap();
+        fs::write(dir.join("oracle-cards-20210104100000.json.gz.part"), "[").unwrap();
+        fs::write(dir.join("rulings-20201231100000.json"), "[]").unwrap();
+
+        let files = cache.list().unwrap();
+        let listed: Vec<_> = files
+            .iter()
+            .map(|file| (file.bulk_type.as_str(), file.updated_at, file.size))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("oracle-cards", day(3), 2),
+                ("oracle-cards", day(2), 2),
+                ("oracle-cards", day(1), 2),
+                ("rulings", day(1), 2),
+                ("rulings", Utc.ymd(2020, 12, 31).and_hms(10, 0, 0), 2),
+            ]
+        );
+
+        let evicted = cache.evict().unwrap();
+        assert_eq!(evicted, files[2..3]);
+        assert!(!files[2].path.exists());
+        assert_eq!(cache.list().unwrap().len(), 4);
+
+        assert_eq!(cache.purge().unwrap().len(), 4);
+        assert!(cache.list().unwrap().is_empty());
+        assert!(!dir
+            .join("oracle-cards-20210104100000.json.gz.part")
+            .exists());
+        assert!(dir.join("notes.json").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -5,12 +5,14 @@
 //! documented in the official [scryfall page](https://scryfall.com/docs/api/cards).
 mod border_color;
 mod card_faces;
+mod collection;
 mod color;
 mod frame;
 mod frame_effect;
 mod game;
 mod layout;
 mod legality;
+mod mana_cost;
 mod preview;
 mod price;
 mod rarity;
@@ -19,29 +21,42 @@ mod related_card;
 use std::collections::hash_map::HashMap;
 
 use chrono::NaiveDate;
+#[cfg(feature = "async")]
+use futures_util::stream::Stream;
 use serde::{Deserialize, Serialize};
 use url::Url;
 use uuid::Uuid;
 
 pub use self::border_color::BorderColor;
 pub use self::card_faces::CardFace;
+pub use self::collection::{CardCollection, CardIdentifier};
 pub use self::color::{Color, Colors, Multicolored};
 pub use self::frame::Frame;
 pub use self::frame_effect::FrameEffect;
 pub use self::game::Game;
 pub use self::layout::Layout;
 pub use self::legality::Legality;
+pub use self::mana_cost::{ManaCost, ManaSymbol};
 pub use self::preview::Preview;
 pub use self::price::Price;
 pub use self::rarity::Rarity;
 pub use self::related_card::RelatedCard;
+use self::collection::{CollectionRequest, CollectionResponse};
+use crate::client::ScryfallClient;
 use crate::format::Format;
 use crate::list::{List, ListIter};
 use crate::ruling::Ruling;
 use crate::search::Search;
 use crate::set::{Set, SetCode, SetType};
 use crate::uri::Uri;
-use crate::util::CARDS_URL;
+use crate::util::cards_url;
+
+/// The names returned by the autocomplete endpoint. Unlike other catalogs,
+/// it has no `uri`.
+#[derive(Deserialize)]
+struct Autocomplete {
+    data: Vec<String>,
+}
 
 /// Card objects represent individual Magic: The Gathering cards that players
 /// could obtain and add to their collection (with a few minor exceptions).
@@ -152,6 +167,10 @@ pub struct Card {
     /// True if this printing exists in a foil version.
     pub foil: bool,
 
+    /// True if this card is on the Commander format's Game Changers list.
+    #[serde(default)]
+    pub game_changer: bool,
+
     /// This card’s hand modifier, if it is Vanguard card. This value will
     /// contain a delta, such as -1.
     pub hand_modifier: Option<String>,
@@ -361,7 +380,7 @@ impl Card {
     /// # }
     /// ```
     pub fn random() -> crate::Result<Card> {
-        Uri::from(CARDS_URL.join("random/")?).fetch()
+        Uri::from(cards_url().join("random/")?).fetch()
     }
 
     /// Returns a [`ListIter`] of the cards that match the search terms.
@@ -400,7 +419,7 @@ impl Card {
     /// # }
     /// ```
     pub fn search(query: impl Search) -> crate::Result<ListIter<Card>> {
-        let mut url = CARDS_URL.join("search/")?;
+        let mut url = cards_url().join("search/")?;
         query.write_query(&mut url)?;
         Uri::from(url).fetch_iter()
     }
@@ -419,11 +438,44 @@ impl Card {
     /// # }
     /// ```
     pub fn search_all(query: impl Search) -> crate::Result<Vec<Card>> {
-        let mut url = CARDS_URL.join("search/")?;
+        let mut url = cards_url().join("search/")?;
         query.write_query(&mut url)?;
         Uri::from(url).fetch_all()
     }
 
+    /// Streams the cards matching a query like [`search`][Card::search],
+    /// requesting following pages without blocking the thread.
+    ///
+    /// ```rust
+    /// # use futures_util::{pin_mut, StreamExt};
+    /// # use scryfall::search::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> scryfall::Result<()> {
+    /// use scryfall::Card;
+    /// let cards = Card::search_async(set("war").and(collector_number(123))).await?;
+    /// pin_mut!(cards);
+    /// assert_eq!(cards.next().await.unwrap()?.name, "Demolish");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn search_async(
+        query: impl Search,
+    ) -> crate::Result<impl Stream<Item = crate::Result<Card>>> {
+        let mut url = cards_url().join("search/")?;
+        query.write_query(&mut url)?;
+        Uri::<List<Card>>::from(url).fetch_iter_async().await
+    }
+
+    /// Returns all cards that match a query like
+    /// [`search_all`][Card::search_all], without blocking the thread.
+    #[cfg(feature = "async")]
+    pub async fn search_all_async(query: impl Search) -> crate::Result<Vec<Card>> {
+        let mut url = cards_url().join("search/")?;
+        query.write_query(&mut url)?;
+        Uri::from(url).fetch_all_async().await
+    }
+
     /// Fetches a random card matching a search query.
     ///
     /// # Examples
@@ -436,7 +488,7 @@ impl Card {
     /// # }
     /// ```
     pub fn search_random(query: impl Search) -> crate::Result<Card> {
-        let mut url = CARDS_URL.join("random/")?;
+        let mut url = cards_url().join("random/")?;
         query.write_query(&mut url)?;
         Uri::from(url).fetch()
     }
@@ -458,7 +510,7 @@ impl Card {
     /// assert!(Card::named("Name that doesn't exist").is_err())
     /// ```
     pub fn named(name: &str) -> crate::Result<Card> {
-        let mut url = CARDS_URL.join("named")?;
+        let mut url = cards_url().join("named")?;
         url.query_pairs_mut().append_pair("exact", name);
         Uri::from(url).fetch()
     }
@@ -474,11 +526,39 @@ impl Card {
     /// }
     /// ```
     pub fn named_fuzzy(query: &str) -> crate::Result<Card> {
-        let mut url = CARDS_URL.join("named")?;
+        let mut url = cards_url().join("named")?;
         url.query_pairs_mut().append_pair("fuzzy", query);
         Uri::from(url).fetch()
     }
 
+    /// Returns up to 20 full English card names that could be autocompletions
+    /// of `query`. Queries shorter than 2 characters return no names.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use scryfall::card::Card;
+    /// let names = Card::autocomplete("thal").unwrap();
+    /// assert!(names.iter().any(|name| name == "Thallid"));
+    /// ```
+    pub fn autocomplete(query: &str) -> crate::Result<Vec<String>> {
+        let uri = Card::autocomplete_uri(query)?;
+        Ok(uri.fetch()?.data)
+    }
+
+    /// Returns the autocompletions of `query` like
+    /// [`autocomplete`][Card::autocomplete], without blocking the thread.
+    #[cfg(feature = "async")]
+    pub async fn autocomplete_async(query: &str) -> crate::Result<Vec<String>> {
+        let uri = Card::autocomplete_uri(query)?;
+        Ok(uri.fetch_async().await?.data)
+    }
+
+    fn autocomplete_uri(query: &str) -> crate::Result<Uri<Autocomplete>> {
+        let mut url = cards_url().join("autocomplete")?;
+        url.query_pairs_mut().append_pair("q", query);
+        Ok(Uri::from(url))
+    }
+
     /// Fetch a card by its set and number.
     ///
     /// # Examples
@@ -490,7 +570,7 @@ impl Card {
     /// }
     /// ```
     pub fn set_and_number(set_code: &str, number: usize) -> crate::Result<Card> {
-        Uri::from(CARDS_URL.join(&format!("{}/{}", set_code, number))?).fetch()
+        Uri::from(cards_url().join(&format!("{}/{}", set_code, number))?).fetch()
     }
 
     /// Fetch a card by its multiverse id.
@@ -505,7 +585,7 @@ impl Card {
     /// ```
     pub fn multiverse(multiverse_id: usize) -> crate::Result<Card> {
         Uri::from(
-            CARDS_URL
+            cards_url()
                 .join("multiverse/")?
                 .join(&multiverse_id.to_string())?,
         )
@@ -523,7 +603,7 @@ impl Card {
     /// }
     /// ```
     pub fn mtgo(mtgo_id: usize) -> crate::Result<Card> {
-        Uri::from(CARDS_URL.join("mtgo/")?.join(&mtgo_id.to_string())?).fetch()
+        Uri::from(cards_url().join("mtgo/")?.join(&mtgo_id.to_string())?).fetch()
     }
 
     /// Fetch a card by its arena id.
@@ -537,7 +617,7 @@ impl Card {
     /// }
     /// ```
     pub fn arena(arena_id: usize) -> crate::Result<Card> {
-        Uri::from(CARDS_URL.join("arena/")?.join(&arena_id.to_string())?).fetch()
+        Uri::from(cards_url().join("arena/")?.join(&arena_id.to_string())?).fetch()
     }
 
     /// Fetch a card by its tcgplayer id.
@@ -552,7 +632,7 @@ impl Card {
     /// ```
     pub fn tcgplayer(tcgplayer_id: usize) -> crate::Result<Card> {
         Uri::from(
-            CARDS_URL
+            cards_url()
                 .join("tcgplayer/")?
                 .join(&tcgplayer_id.to_string())?,
         )
@@ -570,6 +650,56 @@ impl Card {
     /// }
     /// ```
     pub fn card(scryfall_id: Uuid) -> crate::Result<Card> {
-        Uri::from(CARDS_URL.join(&scryfall_id.to_string())?).fetch()
+        Uri::from(cards_url().join(&scryfall_id.to_string())?).fetch()
+    }
+
+    /// The maximum number of identifiers the collection endpoint accepts per
+    /// request.
+    pub const COLLECTION_BATCH_SIZE: usize = 75;
+
+    /// Fetch the cards of many identifiers at once, making one request per
+    /// [`COLLECTION_BATCH_SIZE`][Self::COLLECTION_BATCH_SIZE] identifiers
+    /// instead of one per card.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use scryfall::card::{Card, CardIdentifier};
+    /// let collection = Card::collection(vec![
+    ///     CardIdentifier::Name {
+    ///         name: "Lightning Bolt".to_string(),
+    ///     },
+    ///     CardIdentifier::CollectorNumberAndSet {
+    ///         collector_number: "4".to_string(),
+    ///         set: "vma".to_string(),
+    ///     },
+    ///     CardIdentifier::Name {
+    ///         name: "Name that doesn't exist".to_string(),
+    ///     },
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(collection.cards[0].name, "Lightning Bolt");
+    /// assert_eq!(collection.cards[1].name, "Black Lotus");
+    /// assert_eq!(collection.not_found.len(), 1);
+    /// ```
+    pub fn collection(
+        identifiers: impl IntoIterator<Item = CardIdentifier>,
+    ) -> crate::Result<CardCollection> {
+        Card::collection_with(&ScryfallClient::current(), identifiers)
+    }
+
+    fn collection_with(
+        client: &ScryfallClient,
+        identifiers: impl IntoIterator<Item = CardIdentifier>,
+    ) -> crate::Result<CardCollection> {
+        let identifiers: Vec<_> = identifiers.into_iter().collect();
+        let url = client.endpoint("cards/collection");
+        let mut collection = CardCollection::default();
+        for batch in identifiers.chunks(Card::COLLECTION_BATCH_SIZE) {
+            let response: CollectionResponse =
+                client.post_json(&url, &CollectionRequest { identifiers: batch })?;
+            collection.cards.extend(response.data);
+            collection.not_found.extend(response.not_found);
+        }
+        Ok(collection)
     }
 }
@@ -0,0 +1,171 @@
+//! Identifiers of cards to fetch in bulk from the
+//! [collection](https://scryfall.com/docs/api/cards/collection) endpoint.
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::card::Card;
+
+/// Identifies a card for [`Card::collection`].
+///
+/// Serializes to one of the identifier objects of the
+/// [collection](https://scryfall.com/docs/api/cards/collection) endpoint,
+/// which lists the identifiers that weren't found in the same shape.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+#[serde(untagged)]
+pub enum CardIdentifier {
+    /// A card with a Scryfall id.
+    Id {
+        /// The Scryfall id of the card.
+        id: Uuid,
+    },
+    /// A card with an MTGO id.
+    MtgoId {
+        /// The MTGO id of the card.
+        mtgo_id: usize,
+    },
+    /// A card with a multiverse id.
+    MultiverseId {
+        /// The multiverse id of the card.
+        multiverse_id: usize,
+    },
+    /// The most recent printing of a card with an Oracle id.
+    OracleId {
+        /// The Oracle id of the card.
+        oracle_id: Uuid,
+    },
+    /// The most recent printing of a card with an illustration id.
+    IllustrationId {
+        /// The illustration id of the card.
+        illustration_id: Uuid,
+    },
+    /// The printing of a card with a name in a set. Goes before `Name`, as
+    /// untagged deserialization takes the first variant that fits.
+    NameAndSet {
+        /// The name of the card.
+        name: String,
+        /// The code of the set.
+        set: String,
+    },
+    /// The printing of a card with a collector number in a set.
+    CollectorNumberAndSet {
+        /// The collector number of the card.
+        collector_number: String,
+        /// The code of the set.
+        set: String,
+    },
+    /// The most recent printing of a card with a name.
+    Name {
+        /// The name of the card.
+        name: String,
+    },
+}
+
+/// The cards found by [`Card::collection`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct CardCollection {
+    /// The cards found, in the order of the identifiers.
+    pub cards: Vec<Card>,
+
+    /// The identifiers no card was found for.
+    pub not_found: Vec<CardIdentifier>,
+}
+
+/// The body of a request to the collection endpoint.
+#[derive(Serialize)]
+pub(crate) struct CollectionRequest<'a> {
+    pub identifiers: &'a [CardIdentifier],
+}
+
+/// A response of the collection endpoint.
+#[derive(Deserialize)]
+pub(crate) struct CollectionResponse {
+    pub data: Vec<Card>,
+    #[serde(default)]
+    pub not_found: Vec<CardIdentifier>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, json, to_value, Value};
+
+    use super::*;
+    use crate::util::mock_server::{mock_client, mock_server};
+
+    #[test]
+    fn identifier_serde() {
+        let identifiers = vec![
+            CardIdentifier::Id {
+                id: "0b81b329-4ef5-4b55-9fe7-9ed69477e96b".parse().unwrap(),
+            },
+            CardIdentifier::MultiverseId {
+                multiverse_id: 409574,
+            },
+            CardIdentifier::NameAndSet {
+                name: "Lightning Bolt".to_string(),
+                set: "m11".to_string(),
+            },
+            CardIdentifier::CollectorNumberAndSet {
+                collector_number: "4".to_string(),
+                set: "vma".to_string(),
+            },
+            CardIdentifier::Name {
+                name: "Lightning Bolt".to_string(),
+            },
+        ];
+        let value = to_value(&identifiers).unwrap();
+        assert_eq!(
+            value,
+            json!([
+                {"id": "0b81b329-4ef5-4b55-9fe7-9ed69477e96b"},
+                {"multiverse_id": 409574},
+                {"name": "Lightning Bolt", "set": "m11"},
+                {"collector_number": "4", "set": "vma"},
+                {"name": "Lightning Bolt"},
+            ])
+        );
+        assert_eq!(
+            serde_json::from_value::<Vec<CardIdentifier>>(value).unwrap(),
+            identifiers
+        );
+    }
+
+    #[test]
+    fn collection_in_batches() {
+        let (address, server) = mock_server(vec![
+            (
+                "200 OK",
+                "",
+                r#"{"object": "list", "not_found": [{"name": "Nope"}], "data": []}"#,
+            ),
+            (
+                "200 OK",
+                "",
+                r#"{"object": "list", "not_found": [], "data": []}"#,
+            ),
+        ]);
+        let client = mock_client(address, 0);
+        let identifiers = (0..80).map(|i| CardIdentifier::MtgoId { mtgo_id: i });
+        let collection = Card::collection_with(&client, identifiers).unwrap();
+        assert!(collection.cards.is_empty());
+        assert_eq!(
+            collection.not_found,
+            [CardIdentifier::Name {
+                name: "Nope".to_string()
+            }]
+        );
+
+        let requests = server.join().unwrap();
+        let batch_sizes: Vec<_> = requests
+            .iter()
+            .map(|request| {
+                assert!(request.starts_with("POST /mirror/cards/collection "));
+                let (_, body) = request.split_once("\r\n\r\n").unwrap();
+                from_str::<Value>(body).unwrap()["identifiers"]
+                    .as_array()
+                    .unwrap()
+                    .len()
+            })
+            .collect();
+        assert_eq!(batch_sizes, [75, 5]);
+    }
+}
@@ -0,0 +1,271 @@
+//! Typed mana costs, parsed from the `{2}{G/W}{G/P}` form of
+//! [`Card::mana_cost`][crate::card::Card::mana_cost].
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::card::{Color, Colors};
+use crate::error::Error;
+
+/// A symbol of a mana cost, like `{2}`, `{G/W}` or `{G/P}`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ManaSymbol {
+    /// An amount of generic mana, like `{2}`.
+    Generic(u32),
+    /// A variable amount of generic mana, `{X}`, `{Y}` or `{Z}`.
+    Variable(char),
+    /// One mana of a color, like `{G}`.
+    Colored(Color),
+    /// One colorless mana, `{C}`.
+    Colorless,
+    /// One mana from a snow source, `{S}`.
+    Snow,
+    /// One mana of either of two colors, like `{G/W}`.
+    Hybrid(Color, Color),
+    /// One mana of a color or two generic mana, like `{2/W}`.
+    GenericHybrid(Color),
+    /// One mana of a color or two life, like `{G/P}`.
+    Phyrexian(Color),
+    /// One mana of either of two colors or two life, like `{G/W/P}`.
+    HybridPhyrexian(Color, Color),
+}
+
+impl ManaSymbol {
+    /// The mana value of this symbol. Variable symbols count as 0, and
+    /// `{2/W}` counts as 2.
+    pub fn mana_value(self) -> u32 {
+        match self {
+            ManaSymbol::Generic(amount) => amount,
+            ManaSymbol::Variable(_) => 0,
+            ManaSymbol::GenericHybrid(_) => 2,
+            _ => 1,
+        }
+    }
+
+    /// The colors this symbol can be paid with, apart from generic mana and
+    /// life.
+    pub fn colors(self) -> Vec<Color> {
+        match self {
+            ManaSymbol::Colored(color)
+            | ManaSymbol::GenericHybrid(color)
+            | ManaSymbol::Phyrexian(color) => vec![color],
+            ManaSymbol::Hybrid(first, second) | ManaSymbol::HybridPhyrexian(first, second) => {
+                vec![first, second]
+            },
+            _ => vec![],
+        }
+    }
+
+    /// Parses the inside of the braces of a symbol.
+    fn parse(symbol: &str) -> Option<Self> {
+        let parts: Vec<_> = symbol.split('/').collect();
+        match parts.as_slice() {
+            ["C"] => Some(ManaSymbol::Colorless),
+            ["S"] => Some(ManaSymbol::Snow),
+            [variable @ ("X" | "Y" | "Z")] => variable.chars().next().map(ManaSymbol::Variable),
+            [amount] if !amount.is_empty() && amount.bytes().all(|b| b.is_ascii_digit()) => {
+                amount.parse().ok().map(ManaSymbol::Generic)
+            },
+            [color] => parse_color(color).map(ManaSymbol::Colored),
+            ["2", color] => parse_color(color).map(ManaSymbol::GenericHybrid),
+            [color, "P"] => parse_color(color).map(ManaSymbol::Phyrexian),
+            [first, second] => Some(ManaSymbol::Hybrid(
+                parse_color(first)?,
+                parse_color(second)?,
+            )),
+            [first, second, "P"] => Some(ManaSymbol::HybridPhyrexian(
+                parse_color(first)?,
+                parse_color(second)?,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Parses one of the five colors, not colorless, which is a symbol of its own.
+fn parse_color(color: &str) -> Option<Color> {
+    match color {
+        "W" => Some(Color::White),
+        "U" => Some(Color::Blue),
+        "B" => Some(Color::Black),
+        "R" => Some(Color::Red),
+        "G" => Some(Color::Green),
+        _ => None,
+    }
+}
+
+impl fmt::Display for ManaSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ManaSymbol::Generic(amount) => write!(f, "{{{}}}", amount),
+            ManaSymbol::Variable(variable) => write!(f, "{{{}}}", variable),
+            ManaSymbol::Colored(color) => write!(f, "{{{}}}", color),
+            ManaSymbol::Colorless => write!(f, "{{C}}"),
+            ManaSymbol::Snow => write!(f, "{{S}}"),
+            ManaSymbol::Hybrid(first, second) => write!(f, "{{{}/{}}}", first, second),
+            ManaSymbol::GenericHybrid(color) => write!(f, "{{2/{}}}", color),
+            ManaSymbol::Phyrexian(color) => write!(f, "{{{}/P}}", color),
+            ManaSymbol::HybridPhyrexian(first, second) => {
+                write!(f, "{{{}/{}/P}}", first, second)
+            },
+        }
+    }
+}
+
+/// A mana cost, the symbols of a string like `{2}{G/W}{G/P}` in the order
+/// they're written in.
+///
+/// Parsing and displaying round-trip, and it is (de)serialized as the string.
+///
+/// # Examples
+/// ```rust
+/// use scryfall::card::{Color, ManaCost};
+/// let cost: ManaCost = "{2}{G/W}{G/P}".parse().unwrap();
+/// assert_eq!(cost.mana_value(), 4);
+/// assert_eq!(cost.pips()[&Color::Green], 2);
+/// assert_eq!(cost.pips()[&Color::White], 1);
+/// assert_eq!(cost.to_string(), "{2}{G/W}{G/P}");
+/// ```
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[serde(try_from = "String", into = "String")]
+pub struct ManaCost {
+    /// The symbols of the cost.
+    pub symbols: Vec<ManaSymbol>,
+}
+
+impl ManaCost {
+    /// The mana value of the cost, with variable symbols counting as 0.
+    pub fn mana_value(&self) -> u32 {
+        self.symbols.iter().map(|symbol| symbol.mana_value()).sum()
+    }
+
+    /// The number of symbols of each color. Hybrid symbols count towards each
+    /// of their colors, `{2/W}` and Phyrexian symbols towards their only
+    /// color.
+    pub fn pips(&self) -> HashMap<Color, usize> {
+        let mut pips = HashMap::new();
+        for color in self.symbols.iter().flat_map(|symbol| symbol.colors()) {
+            *pips.entry(color).or_insert(0) += 1;
+        }
+        pips
+    }
+
+    /// The colors of the cost.
+    pub fn colors(&self) -> Colors {
+        self.symbols
+            .iter()
+            .flat_map(|symbol| symbol.colors())
+            .fold(Colors::colorless(), Colors::with)
+    }
+
+    /// Whether the cost has a variable symbol like `{X}`.
+    pub fn is_variable(&self) -> bool {
+        self.symbols
+            .iter()
+            .any(|symbol| matches!(symbol, ManaSymbol::Variable(_)))
+    }
+}
+
+impl FromStr for ManaCost {
+    type Err = Error;
+
+    fn from_str(cost: &str) -> Result<Self, Self::Err> {
+        let mut symbols = vec![];
+        let mut rest = cost;
+        while !rest.is_empty() {
+            let (symbol, after) = rest
+                .strip_prefix('{')
+                .and_then(|rest| rest.split_once('}'))
+                .ok_or_else(|| Error::Other(format!("Invalid mana cost: {}", cost)))?;
+            symbols.push(ManaSymbol::parse(symbol).ok_or_else(|| {
+                Error::Other(format!("Invalid mana symbol {{{}}} in {}", symbol, cost))
+            })?);
+            rest = after;
+        }
+        Ok(ManaCost { symbols })
+    }
+}
+
+impl TryFrom<String> for ManaCost {
+    type Error = Error;
+
+    fn try_from(cost: String) -> Result<Self, Self::Error> {
+        cost.parse()
+    }
+}
+
+impl From<ManaCost> for String {
+    fn from(cost: ManaCost) -> Self {
+        cost.to_string()
+    }
+}
+
+impl fmt::Display for ManaCost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.symbols
+            .iter()
+            .try_for_each(|symbol| write!(f, "{}", symbol))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_symbols() {
+        let cost: ManaCost = "{X}{10}{W}{C}{S}{U/B}{2/R}{G/P}{B/G/P}".parse().unwrap();
+        assert_eq!(
+            cost.symbols,
+            [
+                ManaSymbol::Variable('X'),
+                ManaSymbol::Generic(10),
+                ManaSymbol::Colored(Color::White),
+                ManaSymbol::Colorless,
+                ManaSymbol::Snow,
+                ManaSymbol::Hybrid(Color::Blue, Color::Black),
+                ManaSymbol::GenericHybrid(Color::Red),
+                ManaSymbol::Phyrexian(Color::Green),
+                ManaSymbol::HybridPhyrexian(Color::Black, Color::Green),
+            ]
+        );
+        assert_eq!(cost.mana_value(), 18);
+        assert!(cost.is_variable());
+        assert_eq!(cost.colors(), Colors::ALL);
+    }
+
+    #[test]
+    fn pips() {
+        let cost: ManaCost = "{1}{G}{G/W}{G/P}{C}".parse().unwrap();
+        let pips = cost.pips();
+        assert_eq!(pips[&Color::Green], 3);
+        assert_eq!(pips[&Color::White], 1);
+        assert_eq!(pips.get(&Color::Colorless), None);
+    }
+
+    #[test]
+    fn round_trip() {
+        for cost in [
+            "",
+            "{0}",
+            "{X}{X}{R}",
+            "{2}{G/W}{G/P}",
+            "{W/U/P}{2/B}{S}{C}",
+        ] {
+            assert_eq!(cost.parse::<ManaCost>().unwrap().to_string(), cost);
+        }
+        let cost: ManaCost = serde_json::from_str(r#""{3}{U}{U}""#).unwrap();
+        assert_eq!(cost.mana_value(), 5);
+        assert_eq!(serde_json::to_string(&cost).unwrap(), r#""{3}{U}{U}""#);
+    }
+
+    #[test]
+    fn invalid_costs() {
+        for cost in ["G", "{G", "{}", "{Q}", "{G/C}", "{2/P}", "{1}{G} // {U}"] {
+            assert!(cost.parse::<ManaCost>().is_err(), "{}", cost);
+        }
+    }
+}
@@ -8,7 +8,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::uri::Uri;
-use crate::util::CATALOG_URL;
+use crate::util::catalog_url;
 
 /// A Catalog object contains an array of Magic datapoints (words, card values,
 /// etc). Catalog objects are provided by the API as aids for building other
@@ -37,7 +37,7 @@ impl Catalog {
     /// assert!(Catalog::card_names().unwrap().data.len() > 0)
     /// ```
     pub fn card_names() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("card-names")?).fetch()
+        Uri::from(catalog_url().join("card-names")?).fetch()
     }
 
     /// Returns a list of all canonical artist names in Scryfall’s database.
@@ -51,7 +51,7 @@ impl Catalog {
     /// assert!(Catalog::artist_names().unwrap().data.len() > 0)
     /// ```
     pub fn artist_names() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("artist-names")?).fetch()
+        Uri::from(catalog_url().join("artist-names")?).fetch()
     }
 
     /// Returns a Catalog of all English words, of length 2 or more, that could
@@ -65,7 +65,7 @@ impl Catalog {
     /// assert!(Catalog::word_bank().unwrap().data.len() > 0)
     /// ```
     pub fn word_bank() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("word-bank")?).fetch()
+        Uri::from(catalog_url().join("word-bank")?).fetch()
     }
 
     /// Returns a Catalog of all creature types in Scryfall’s database. Values
@@ -77,7 +77,7 @@ impl Catalog {
     /// assert!(Catalog::creature_types().unwrap().data.len() > 0)
     /// ```
     pub fn creature_types() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("creature-types")?).fetch()
+        Uri::from(catalog_url().join("creature-types")?).fetch()
     }
 
     /// Returns a Catalog of all Planeswalker types in Scryfall’s database.
@@ -90,7 +90,7 @@ impl Catalog {
     /// assert!(Catalog::planeswalker_types().unwrap().data.len() > 0)
     /// ```
     pub fn planeswalker_types() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("planeswalker-types")?).fetch()
+        Uri::from(catalog_url().join("planeswalker-types")?).fetch()
     }
 
     /// Returns a Catalog of all Land types in Scryfall’s database. Values are
@@ -102,7 +102,7 @@ impl Catalog {
     /// assert!(Catalog::land_types().unwrap().data.len() > 0)
     /// ```
     pub fn land_types() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("land-types")?).fetch()
+        Uri::from(catalog_url().join("land-types")?).fetch()
     }
 
     /// Returns a Catalog of all artifact types in Scryfall’s database. Values
@@ -114,7 +114,7 @@ impl Catalog {
     /// assert!(Catalog::artifact_types().unwrap().data.len() > 0)
     /// ```
     pub fn artifact_types() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("artifact-types")?).fetch()
+        Uri::from(catalog_url().join("artifact-types")?).fetch()
     }
 
     /// Returns a Catalog of all enchantment types in Scryfall’s database.
@@ -127,7 +127,7 @@ impl Catalog {
     /// assert!(Catalog::enchantment_types().unwrap().data.len() > 0)
     /// ```
     pub fn enchantment_types() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("enchantment-types")?).fetch()
+        Uri::from(catalog_url().join("enchantment-types")?).fetch()
     }
 
     /// Returns a Catalog of all spell types in Scryfall’s database. Values are
@@ -139,7 +139,7 @@ impl Catalog {
     /// assert!(Catalog::spell_types().unwrap().data.len() > 0)
     /// ```
     pub fn spell_types() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("spell-types")?).fetch()
+        Uri::from(catalog_url().join("spell-types")?).fetch()
     }
 
     /// Returns a Catalog of all possible values for a creature or vehicle’s
@@ -152,7 +152,7 @@ impl Catalog {
     /// assert!(Catalog::powers().unwrap().data.len() > 0)
     /// ```
     pub fn powers() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("powers")?).fetch()
+        Uri::from(catalog_url().join("powers")?).fetch()
     }
 
     /// Returns a Catalog of all possible values for a creature or vehicle’s
@@ -165,7 +165,7 @@ impl Catalog {
     /// assert!(Catalog::toughnesses().unwrap().data.len() > 0)
     /// ```
     pub fn toughnesses() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("toughnesses")?).fetch()
+        Uri::from(catalog_url().join("toughnesses")?).fetch()
     }
 
     /// Returns a Catalog of all possible values for a Planeswalker’s loyalty in
@@ -178,7 +178,7 @@ impl Catalog {
     /// assert!(Catalog::loyalties().unwrap().data.len() > 0)
     /// ```
     pub fn loyalties() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("loyalties")?).fetch()
+        Uri::from(catalog_url().join("loyalties")?).fetch()
     }
 
     /// Returns a Catalog of all card watermarks in Scryfall’s database. Values
@@ -190,7 +190,7 @@ impl Catalog {
     /// assert!(Catalog::watermarks().unwrap().data.len() > 0)
     /// ```
     pub fn watermarks() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("watermarks")?).fetch()
+        Uri::from(catalog_url().join("watermarks")?).fetch()
     }
 
     /// Returns a Catalog of all keyword abilities in Scryfall’s database.
@@ -209,7 +209,7 @@ impl Catalog {
     /// );
     /// ```
     pub fn keyword_abilities() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("keyword-abilities")?).fetch()
+        Uri::from(catalog_url().join("keyword-abilities")?).fetch()
     }
 
     /// Returns a Catalog of all keyword actions in Scryfall’s database. Values
@@ -228,7 +228,7 @@ impl Catalog {
     /// );
     /// ```
     pub fn keyword_actions() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("keyword-actions")?).fetch()
+        Uri::from(catalog_url().join("keyword-actions")?).fetch()
     }
 
     /// Returns a Catalog of all ability words in Scryfall’s database. Values
@@ -247,6 +247,49 @@ impl Catalog {
     /// );
     /// ```
     pub fn ability_words() -> crate::Result<Self> {
-        Uri::from(CATALOG_URL.join("ability-words")?).fetch()
+        Uri::from(catalog_url().join("ability-words")?).fetch()
+    }
+}
+
+#[cfg(feature = "async")]
+macro_rules! async_catalog_fns {
+    ($($meth:ident => $sync:ident, $path:literal;)*) => {
+        $(
+            #[doc = concat!(
+                "Async version of [`", stringify!($sync), "`][Catalog::", stringify!($sync), "]."
+            )]
+            pub async fn $meth() -> crate::Result<Self> {
+                Self::fetch_async($path).await
+            }
+        )*
+    };
+}
+
+/// Async versions of the catalog requests, awaiting the response instead of
+/// blocking the thread.
+#[cfg(feature = "async")]
+impl Catalog {
+    /// Fetches the catalog at `path` relative to the catalog endpoint.
+    async fn fetch_async(path: &str) -> crate::Result<Self> {
+        Uri::from(catalog_url().join(path)?).fetch_async().await
+    }
+
+    async_catalog_fns! {
+        card_names_async => card_names, "card-names";
+        artist_names_async => artist_names, "artist-names";
+        word_bank_async => word_bank, "word-bank";
+        creature_types_async => creature_types, "creature-types";
+        planeswalker_types_async => planeswalker_types, "planeswalker-types";
+        land_types_async => land_types, "land-types";
+        artifact_types_async => artifact_types, "artifact-types";
+        enchantment_types_async => enchantment_types, "enchantment-types";
+        spell_types_async => spell_types, "spell-types";
+        powers_async => powers, "powers";
+        toughnesses_async => toughnesses, "toughnesses";
+        loyalties_async => loyalties, "loyalties";
+        watermarks_async => watermarks, "watermarks";
+        keyword_abilities_async => keyword_abilities, "keyword-abilities";
+        keyword_actions_async => keyword_actions, "keyword-actions";
+        ability_words_async => ability_words, "ability-words";
     }
 }
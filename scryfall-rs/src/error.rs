@@ -30,6 +30,11 @@ pub enum Error {
     #[error("Error making request: {0}")]
     UreqError(Box<UreqError>, String),
 
+    /// Something went wrong when making an async HTTP request.
+    #[cfg(feature = "async")]
+    #[error("Error making request: {0}")]
+    ReqwestError(Box<reqwest::Error>, String),
+
     /// Scryfall error. Please refer to the [official docs](https://scryfall.com/docs/api/errors).
     #[error("Scryfall error: {0}")]
     ScryfallError(ScryfallError),
@@ -37,6 +37,18 @@
 //! assert!(Catalog::card_names().unwrap().data.len() > 0)
 //! ```
 //!
+//! ## Async
+//!
+//! With the `async` feature enabled, requests can be awaited instead of
+//! blocking the thread. The async versions are suffixed with `_async`, e.g.
+//! `Uri::fetch_async`, `Card::search_async` or `Catalog::card_names_async`.
+//! Lists and bulk data files are returned as `Stream`s.
+//!
+//! ```rust,ignore
+//! use scryfall::catalog::Catalog;
+//! assert!(Catalog::card_names_async().await.unwrap().data.len() > 0)
+//! ```
+//!
 //! ## Advanced Search
 //!
 //! One of the main features of `scryfall` is its advanced search.
@@ -46,12 +58,14 @@
 pub mod bulk;
 pub mod card;
 pub mod catalog;
+pub mod client;
 pub mod error;
 pub mod format;
 pub mod list;
 pub mod ruling;
 pub mod search;
 pub mod set;
+pub mod symbology;
 pub mod uri;
 mod util;
 
@@ -61,6 +75,7 @@ pub type Result<T> = std::result::Result<T, error::Error>;
 
 pub use card::Card;
 pub use catalog::Catalog;
+pub use client::ScryfallClient;
 pub use error::Error;
 pub use ruling::Ruling;
 pub use set::Set;
@@ -14,7 +14,7 @@ use uuid::Uuid;
 
 use crate::list::ListIter;
 use crate::uri::Uri;
-use crate::util::{API_RULING, CARDS_URL};
+use crate::util::{cards_url, API_RULING};
 
 /// Rulings represent Oracle rulings, Wizards of the Coast set release notes, or
 /// Scryfall notes for a particular card.
@@ -72,7 +72,7 @@ impl Ruling {
     /// ```
     pub fn multiverse_id(id: usize) -> crate::Result<ListIter<Self>> {
         Uri::from(
-            CARDS_URL
+            cards_url()
                 .join("multiverse/")?
                 .join(&format!("{}/", id))?
                 .join(API_RULING)?,
@@ -96,7 +96,7 @@ impl Ruling {
     /// ```
     pub fn mtgo_id(id: usize) -> crate::Result<ListIter<Self>> {
         Uri::from(
-            CARDS_URL
+            cards_url()
                 .join("mtgo/")?
                 .join(&format!("{}/", id))?
                 .join(API_RULING)?,
@@ -120,7 +120,7 @@ impl Ruling {
     /// ```
     pub fn arena_id(id: usize) -> crate::Result<ListIter<Self>> {
         Uri::from(
-            CARDS_URL
+            cards_url()
                 .join("arena/")?
                 .join(&format!("{}/", id))?
                 .join(API_RULING)?,
@@ -143,7 +143,7 @@ impl Ruling {
     /// ```
     pub fn set_and_number(set: &str, number: u32) -> crate::Result<ListIter<Self>> {
         Uri::from(
-            CARDS_URL
+            cards_url()
                 .join(&format!("{}/{}/", set, number))?
                 .join(API_RULING)?,
         )
@@ -163,6 +163,6 @@ impl Ruling {
     /// );
     /// ```
     pub fn uuid(id: Uuid) -> crate::Result<ListIter<Self>> {
-        Uri::from(CARDS_URL.join(&format!("{}/", id))?.join(API_RULING)?).fetch_iter()
+        Uri::from(cards_url().join(&format!("{}/", id))?.join(API_RULING)?).fetch_iter()
     }
 }
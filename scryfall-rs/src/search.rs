@@ -29,6 +29,7 @@ use crate::Card;
 
 pub mod advanced;
 pub mod param;
+pub mod parse;
 pub mod query;
 
 /// A type implementing `Search` can be turned into a Scryfall query. This is
@@ -167,6 +168,7 @@ pub mod prelude {
         Regex,
     };
     pub use super::param::{exact, Param};
+    pub use super::parse::ParseError;
     pub use super::query::{not, Query};
     pub use super::Search;
 }
@@ -31,8 +31,13 @@ use crate::search::Search;
 
 pub mod compare;
 pub mod criteria;
+mod matches;
 pub mod value;
 
+pub(crate) use self::matches::blank_card;
+#[cfg(test)]
+pub(crate) use self::matches::tests::abomination;
+
 /// A filter to provide to the search to reduce the cards returned.
 ///
 /// A `Param` can be an [exact card name][exact()], a [`Criterion`], or a
@@ -65,19 +70,19 @@ impl Search for Param {
 }
 
 impl Param {
-    fn exact(value: impl Into<String>) -> Self {
+    pub(super) fn exact(value: impl Into<String>) -> Self {
         Param(ParamImpl::ExactName(value.into()))
     }
 
-    fn criterion(criterion: Criterion) -> Self {
+    pub(super) fn criterion(criterion: Criterion) -> Self {
         Param(ParamImpl::Criterion(criterion))
     }
 
-    fn value(kind: ValueKind, value: impl ToString) -> Self {
+    pub(super) fn value(kind: ValueKind, value: impl ToString) -> Self {
         Param(ParamImpl::Value(kind, value.to_string()))
     }
 
-    fn comparison(kind: ValueKind, op: CompareOp, value: impl ToString) -> Self {
+    pub(super) fn comparison(kind: ValueKind, op: CompareOp, value: impl ToString) -> Self {
         Param(ParamImpl::Comparison(kind, op, value.to_string()))
     }
 }
@@ -39,6 +39,13 @@ impl<T: fmt::Display> fmt::Display for Compare<T> {
     }
 }
 
+impl<T: PartialOrd> Compare<T> {
+    /// Whether `value` satisfies this comparison, e.g. `gte(5).matches(&7)`.
+    pub fn matches(&self, value: &T) -> bool {
+        self.op.matches(value.partial_cmp(&self.value))
+    }
+}
+
 impl<T: ParamValue> ParamValue for Compare<T> {
     fn into_param(self, kind: ValueKind) -> Param {
         Param::comparison(kind, self.op, self.value)
@@ -46,7 +53,7 @@ impl<T: ParamValue> ParamValue for Compare<T> {
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-pub(super) enum CompareOp {
+pub(in crate::search) enum CompareOp {
     Lte,
     Lt,
     Gte,
@@ -53,6 +53,38 @@ impl fmt::Display for Criterion {
     }
 }
 
+impl Criterion {
+    /// Finds the criterion written as `key:value`, like `is:commander`. `is`
+    /// and `has` are synonyms, and `not` is the same as `is` (negating is up
+    /// to the caller).
+    pub(in crate::search) fn parse(key: &str, value: &str) -> Option<Criterion> {
+        let key = match key {
+            "has" | "not" => "is",
+            key => key,
+        };
+        let matches = |criterion: &dyn fmt::Display| {
+            let criterion = criterion.to_string();
+            match criterion.split_once(':') {
+                Some(("has", criterion_value)) => key == "is" && criterion_value == value,
+                Some((criterion_key, criterion_value)) => {
+                    criterion_key == key && criterion_value == value
+                },
+                None => false,
+            }
+        };
+        let card = CARD_IS
+            .iter()
+            .find(|criterion| matches(*criterion))
+            .map(|criterion| Criterion::Card(*criterion));
+        card.or_else(|| {
+            PRINTING_IS
+                .iter()
+                .find(|criterion| matches(*criterion))
+                .map(|criterion| Criterion::Printing(*criterion))
+        })
+    }
+}
+
 impl From<Criterion> for Query {
     fn from(criterion: Criterion) -> Self {
         Query::Param(Param::criterion(criterion))
@@ -141,6 +173,8 @@ pub enum CardIs {
     Companion,
     /// Find cards on the reserved list.
     Reserved,
+    /// Find cards on the Commander Game Changers list.
+    GameChanger,
 
     /// A cycling dual land, such as [Fetid Pools](https://scryfall.com/card/akh/243).
     BicycleLand,
@@ -201,6 +235,52 @@ pub enum CardIs {
     BattleLand,
 }
 
+/// All card criteria, for parsing.
+const CARD_IS: [CardIs; 42] = [
+    CardIs::ColorIndicator,
+    CardIs::EvenCmc,
+    CardIs::OddCmc,
+    CardIs::Phyrexian,
+    CardIs::Hybrid,
+    CardIs::Split,
+    CardIs::Flip,
+    CardIs::Transform,
+    CardIs::ModalDfc,
+    CardIs::Meld,
+    CardIs::Leveler,
+    CardIs::Spell,
+    CardIs::Permanent,
+    CardIs::Historic,
+    CardIs::Party,
+    CardIs::Modal,
+    CardIs::Vanilla,
+    CardIs::FrenchVanilla,
+    CardIs::Funny,
+    CardIs::Commander,
+    CardIs::Brawler,
+    CardIs::Companion,
+    CardIs::Reserved,
+    CardIs::GameChanger,
+    CardIs::BicycleLand,
+    CardIs::TricycleLand,
+    CardIs::BounceLand,
+    CardIs::CanopyLand,
+    CardIs::CheckLand,
+    CardIs::DualLand,
+    CardIs::FastLand,
+    CardIs::FetchLand,
+    CardIs::FilterLand,
+    CardIs::GainLand,
+    CardIs::PainLand,
+    CardIs::ScryLand,
+    CardIs::ShadowLand,
+    CardIs::ShockLand,
+    CardIs::StorageLand,
+    CardIs::CreatureLand,
+    CardIs::TriLand,
+    CardIs::BattleLand,
+];
+
 impl fmt::Display for CardIs {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -237,6 +317,7 @@ impl fmt::Display for CardIs {
                 CardIs::Brawler => "brawler",
                 CardIs::Companion => "companion",
                 CardIs::Reserved => "reserved",
+                CardIs::GameChanger => "gamechanger",
 
                 CardIs::BicycleLand => "bicycle_land",
                 CardIs::TricycleLand => "tricycle_land",
@@ -346,6 +427,29 @@ pub enum PrintingIs {
     Reprint,
 }
 
+/// All printing criteria, for parsing.
+const PRINTING_IS: [PrintingIs; 19] = [
+    PrintingIs::NewCard,
+    PrintingIs::NewRarity,
+    PrintingIs::NewArt,
+    PrintingIs::NewArtist,
+    PrintingIs::NewFlavor,
+    PrintingIs::NewFrame,
+    PrintingIs::NewLanguage,
+    PrintingIs::Watermark,
+    PrintingIs::Full,
+    PrintingIs::Nonfoil,
+    PrintingIs::Foil,
+    PrintingIs::HiRes,
+    PrintingIs::Digital,
+    PrintingIs::Promo,
+    PrintingIs::Spotlight,
+    PrintingIs::Masterpiece,
+    PrintingIs::Unique,
+    PrintingIs::FirstPrint,
+    PrintingIs::Reprint,
+];
+
 impl fmt::Display for PrintingIs {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -402,6 +506,31 @@ mod tests {
     use super::*;
     use crate::search::Search;
 
+    #[test]
+    fn parse_all_criteria() {
+        for criterion in CardIs::iter() {
+            let written = criterion.to_string();
+            let (key, value) = written.split_once(':').unwrap();
+            assert_eq!(
+                Criterion::parse(key, value),
+                Some(Criterion::Card(criterion))
+            );
+        }
+        for criterion in PrintingIs::iter() {
+            let written = criterion.to_string();
+            let (key, value) = written.split_once(':').unwrap();
+            assert_eq!(
+                Criterion::parse(key, value),
+                Some(Criterion::Printing(criterion))
+            );
+        }
+        assert_eq!(
+            Criterion::parse("is", "watermark"),
+            Some(Criterion::Printing(PrintingIs::Watermark))
+        );
+        assert_eq!(Criterion::parse("is", "broken"), None);
+    }
+
     #[test]
     #[ignore]
     fn all_card_is() {
@@ -0,0 +1,742 @@
+//! Local evaluation of search parameters against cards, so cards stored
+//! offline can be filtered with the same queries used to search Scryfall.
+//!
+//! Parameters that depend on more than the card itself, like other printings
+//! (`in:`), cubes or prices of the cheapest printing, can't be evaluated, and
+//! neither can regular expressions.
+use std::cmp::Ordering;
+
+use chrono::{Datelike, NaiveDate};
+use serde::de::DeserializeOwned;
+
+use super::compare::CompareOp;
+use super::criteria::{CardIs, Criterion, PrintingIs};
+use super::value::{NumProperty, ValueKind, ValueKindImpl};
+use super::{Param, ParamImpl};
+use crate::card::{BorderColor, Card, Color, Colors, Layout, Legality, ManaCost, ManaSymbol};
+use crate::error::Error;
+use crate::format::Format;
+use crate::set::SetType;
+
+impl Param {
+    /// Whether `card` matches this parameter, evaluated locally instead of by
+    /// Scryfall. Fails for parameters that can't be evaluated locally.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// # use scryfall::search::prelude::*;
+    /// # fn main() -> scryfall::Result<()> {
+    /// let bolt = scryfall::Card::named("Lightning Bolt")?;
+    /// assert!(cmc(1).matches(&bolt)?);
+    /// assert!(!type_line("creature").matches(&bolt)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn matches(&self, card: &Card) -> crate::Result<bool> {
+        let matches = match &self.0 {
+            ParamImpl::ExactName(name) => Some(card.name.eq_ignore_ascii_case(name)),
+            ParamImpl::Criterion(criterion) => return criterion.matches(card),
+            ParamImpl::Value(kind, value) => kind.matches(None, value, card),
+            ParamImpl::Comparison(kind, op, value) => kind.matches(Some(*op), value, card),
+        };
+        matches.ok_or_else(|| unsupported(self))
+    }
+}
+
+impl Criterion {
+    /// Whether `card` has this property, evaluated locally instead of by
+    /// Scryfall. Fails for criteria that can't be evaluated locally, like land
+    /// cycles.
+    pub fn matches(&self, card: &Card) -> crate::Result<bool> {
+        match self {
+            Criterion::Card(inner) => inner.matches(card),
+            Criterion::Printing(inner) => inner.matches(card),
+        }
+    }
+}
+
+impl CardIs {
+    /// Whether `card` has this property, like
+    /// [`Criterion::matches`].
+    pub fn matches(&self, card: &Card) -> crate::Result<bool> {
+        let type_line = card.type_line.as_str();
+        let has_type = |types: &[&str]| types.iter().any(|t| type_line.contains(t));
+        let matches = match self {
+            CardIs::ColorIndicator => {
+                card.color_indicator.is_some()
+                    || faces(card).any(|face| face.color_indicator.is_some())
+            },
+            CardIs::EvenCmc => card.cmc as u64 % 2 == 0,
+            CardIs::OddCmc => card.cmc as u64 % 2 == 1,
+            CardIs::Phyrexian => {
+                mana_costs(card).any(|cost| {
+                    cost.symbols.iter().any(|symbol| {
+                        matches!(
+                            symbol,
+                            ManaSymbol::Phyrexian(_) | ManaSymbol::HybridPhyrexian(..)
+                        )
+                    })
+                }) || oracle_text(card).contains("/P}")
+            },
+            CardIs::Hybrid => mana_costs(card).any(|cost| {
+                cost.symbols.iter().any(|symbol| {
+                    matches!(
+                        symbol,
+                        ManaSymbol::Hybrid(..)
+                            | ManaSymbol::GenericHybrid(_)
+                            | ManaSymbol::HybridPhyrexian(..)
+                    )
+                })
+            }),
+            CardIs::Split => card.layout == Layout::Split,
+            CardIs::Flip => card.layout == Layout::Flip,
+            CardIs::Transform => card.layout == Layout::Transform,
+            CardIs::ModalDfc => card.layout == Layout::ModalDfc,
+            CardIs::Meld => card.layout == Layout::Meld,
+            CardIs::Leveler => card.layout == Layout::Leveler,
+            CardIs::Spell => {
+                !has_type(&["Land"])
+                    && has_type(&[
+                        "Artifact",
+                        "Battle",
+                        "Creature",
+                        "Enchantment",
+                        "Instant",
+                        "Planeswalker",
+                        "Sorcery",
+                    ])
+            },
+            CardIs::Permanent => has_type(&[
+                "Artifact",
+                "Battle",
+                "Creature",
+                "Enchantment",
+                "Land",
+                "Planeswalker",
+            ]),
+            CardIs::Historic => has_type(&["Artifact", "Legendary", "Saga"]),
+            CardIs::Party => {
+                has_type(&["Creature"]) && has_type(&["Cleric", "Rogue", "Warrior", "Wizard"])
+            },
+            CardIs::Vanilla => has_type(&["Creature"]) && oracle_text(card).is_empty(),
+            CardIs::Funny => {
+                card.set_type == SetType::Funny || card.border_color == BorderColor::Silver
+            },
+            CardIs::Commander => {
+                (has_type(&["Legendary"]) && has_type(&["Creature"]))
+                    || oracle_text(card).contains("can be your commander")
+            },
+            CardIs::Brawler => {
+                (has_type(&["Legendary"]) && has_type(&["Creature", "Planeswalker"]))
+                    || oracle_text(card).contains("can be your commander")
+            },
+            CardIs::Companion => card.keywords.iter().any(|keyword| keyword == "Companion"),
+            CardIs::Reserved => card.reserved,
+            CardIs::GameChanger => card.game_changer,
+            _ => return Err(unsupported(self)),
+        };
+        Ok(matches)
+    }
+}
+
+impl PrintingIs {
+    /// Whether the printing `card` has this property, like
+    /// [`Criterion::matches`].
+    pub fn matches(&self, card: &Card) -> crate::Result<bool> {
+        let matches = match self {
+            PrintingIs::Watermark => card.watermark.is_some(),
+            PrintingIs::Full => card.full_art,
+            PrintingIs::Nonfoil => card.nonfoil,
+            PrintingIs::Foil => card.foil,
+            PrintingIs::HiRes => card.highres_image,
+            PrintingIs::Digital => card.digital,
+            PrintingIs::Promo => card.promo,
+            PrintingIs::Spotlight => card.story_spotlight,
+            PrintingIs::FirstPrint => !card.reprint,
+            PrintingIs::Reprint => card.reprint,
+            _ => return Err(unsupported(self)),
+        };
+        Ok(matches)
+    }
+}
+
+impl ValueKind {
+    /// Evaluates the parameter of this kind with `op` (`None` for `:`) and
+    /// `value` against `card`, or `None` if it can't be evaluated locally.
+    pub(super) fn matches(&self, op: Option<CompareOp>, value: &str, card: &Card) -> Option<bool> {
+        match self.0 {
+            ValueKindImpl::Color => compare_colors(card_colors(card), op, value, CompareOp::Gte),
+            ValueKindImpl::ColorIdentity => compare_colors(
+                Colors::from_slice(&card.color_identity),
+                op,
+                value,
+                CompareOp::Lte,
+            ),
+            ValueKindImpl::Produces => compare_colors(
+                Colors::from_slice(card.produced_mana.as_deref().unwrap_or_default()),
+                op,
+                value,
+                CompareOp::Gte,
+            ),
+            ValueKindImpl::Name => contains_text(op, value, &card.name),
+            ValueKindImpl::Type => contains_text(op, value, &type_line(card)),
+            ValueKindImpl::Oracle | ValueKindImpl::FullOracle => {
+                // Scryfall replaces `~` with the name of the card.
+                let value = value.replace('~', &card.name);
+                contains_text(op, &value, &oracle_text(card))
+            },
+            ValueKindImpl::Flavor => {
+                contains_text(op, value, card.flavor_text.as_deref().unwrap_or_default())
+            },
+            ValueKindImpl::Artist => {
+                contains_text(op, value, card.artist.as_deref().unwrap_or_default())
+            },
+            ValueKindImpl::Keyword => {
+                let keyword = text(op, value)?;
+                Some(card.keywords.iter().any(|k| k.to_lowercase() == keyword))
+            },
+            ValueKindImpl::Watermark => {
+                let watermark = text(op, value)?;
+                Some(card.watermark.as_deref().map(str::to_lowercase) == Some(watermark))
+            },
+            ValueKindImpl::Set => Some(text(op, value)? == card.set.get().to_lowercase()),
+            ValueKindImpl::Number => Some(text(op, value)? == card.collector_number.to_lowercase()),
+            ValueKindImpl::Language => Some(text(op, value)? == card.lang.to_lowercase()),
+            ValueKindImpl::SetType => {
+                Some(parse_enum::<SetType>(&text(op, value)?)? == card.set_type)
+            },
+            ValueKindImpl::BorderColor => {
+                Some(parse_enum::<BorderColor>(&text(op, value)?)? == card.border_color)
+            },
+            ValueKindImpl::Game => Some(card.games.contains(&parse_enum(&text(op, value)?)?)),
+            ValueKindImpl::Rarity => {
+                let rarity = match unquote(value).to_lowercase().as_str() {
+                    "c" => "common".to_string(),
+                    "u" => "uncommon".to_string(),
+                    "r" => "rare".to_string(),
+                    "s" => "special".to_string(),
+                    "m" => "mythic".to_string(),
+                    "b" => "bonus".to_string(),
+                    rarity => rarity.to_string(),
+                };
+                let rarity = parse_enum(&rarity)?;
+                Some(
+                    op.unwrap_or(CompareOp::Eq)
+                        .matches(card.rarity.partial_cmp(&rarity)),
+                )
+            },
+            ValueKindImpl::Format => {
+                legality(op, value, card, &[Legality::Legal, Legality::Restricted])
+            },
+            ValueKindImpl::Banned => legality(op, value, card, &[Legality::Banned]),
+            ValueKindImpl::Restricted => legality(op, value, card, &[Legality::Restricted]),
+            ValueKindImpl::Date => {
+                let date = NaiveDate::parse_from_str(unquote(value), "%Y-%m-%d").ok()?;
+                Some(
+                    op.unwrap_or(CompareOp::Eq)
+                        .matches(card.released_at.partial_cmp(&date)),
+                )
+            },
+            ValueKindImpl::NumericComparable(property) => {
+                let card_value = numeric_property(card, property)?;
+                let value = match value.parse::<f64>() {
+                    Ok(value) => Some(value),
+                    Err(_) => numeric_property(card, parse_num_property(value)?)?,
+                };
+                Some(match (card_value, value) {
+                    (Some(card_value), Some(value)) => op
+                        .unwrap_or(CompareOp::Eq)
+                        .matches(card_value.partial_cmp(&value)),
+                    _ => false,
+                })
+            },
+            _ => None,
+        }
+    }
+}
+
+impl CompareOp {
+    /// Whether the left side of a comparison ordered as `ordering` relative to
+    /// the right side satisfies this operator. Incomparable values never do.
+    pub(super) fn matches(self, ordering: Option<Ordering>) -> bool {
+        match (self, ordering) {
+            (_, None) => false,
+            (CompareOp::Lt, Some(ordering)) => ordering == Ordering::Less,
+            (CompareOp::Lte, Some(ordering)) => ordering != Ordering::Greater,
+            (CompareOp::Gt, Some(ordering)) => ordering == Ordering::Greater,
+            (CompareOp::Gte, Some(ordering)) => ordering != Ordering::Less,
+            (CompareOp::Eq, Some(ordering)) => ordering == Ordering::Equal,
+            (CompareOp::Neq, Some(ordering)) => ordering != Ordering::Equal,
+        }
+    }
+}
+
+/// A card without any properties. Whether a parameter can be evaluated
+/// locally depends on the parameter alone, so evaluating it against this card
+/// tells whether it can be evaluated against any card.
+pub(crate) fn blank_card() -> Card {
+    serde_json::from_str(
+        r#"{
+        "object": "card",
+        "id": "00000000-0000-0000-0000-000000000000",
+        "oracle_id": "00000000-0000-0000-0000-000000000000",
+        "name": "",
+        "lang": "en",
+        "released_at": "1993-08-05",
+        "uri": "https://api.scryfall.com/cards/",
+        "scryfall_uri": "https://scryfall.com/card/",
+        "layout": "normal",
+        "highres_image": false,
+        "cmc": 0.0,
+        "type_line": "",
+        "color_identity": [],
+        "keywords": [],
+        "legalities": {},
+        "games": [],
+        "reserved": false,
+        "foil": false,
+        "nonfoil": false,
+        "oversized": false,
+        "promo": false,
+        "reprint": false,
+        "variation": false,
+        "set": "lea",
+        "set_name": "",
+        "set_type": "core",
+        "set_uri": "https://api.scryfall.com/sets/",
+        "set_search_uri": "https://api.scryfall.com/cards/search",
+        "scryfall_set_uri": "https://scryfall.com/sets/",
+        "rulings_uri": "https://api.scryfall.com/cards/",
+        "prints_search_uri": "https://api.scryfall.com/cards/search",
+        "collector_number": "",
+        "digital": false,
+        "rarity": "common",
+        "border_color": "black",
+        "frame": "1993",
+        "full_art": false,
+        "textless": false,
+        "booster": false,
+        "story_spotlight": false,
+        "prices": {},
+        "related_uris": {},
+        "purchase_uris": {}
+        }"#,
+    )
+    .expect("Blank card")
+}
+
+fn unsupported(param: &impl std::fmt::Display) -> Error {
+    Error::Other(format!("{} can't be evaluated locally", param))
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// The lowercase text of a `:` parameter, or `None` for comparisons and
+/// regular expressions.
+fn text(op: Option<CompareOp>, value: &str) -> Option<String> {
+    let is_regex = value.len() >= 2 && value.starts_with('/') && value.ends_with('/');
+    if op.is_some() || is_regex {
+        return None;
+    }
+    Some(unquote(value).to_lowercase())
+}
+
+fn contains_text(op: Option<CompareOp>, value: &str, card_text: &str) -> Option<bool> {
+    Some(card_text.to_lowercase().contains(&text(op, value)?))
+}
+
+fn parse_enum<T: DeserializeOwned>(value: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string())).ok()
+}
+
+fn faces(card: &Card) -> impl Iterator<Item = &crate::card::CardFace> {
+    card.card_faces.iter().flatten()
+}
+
+fn oracle_text(card: &Card) -> String {
+    match &card.oracle_text {
+        Some(text) => text.clone(),
+        None => faces(card)
+            .filter_map(|face| face.oracle_text.as_deref())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn type_line(card: &Card) -> String {
+    let faces = faces(card).filter_map(|face| face.type_line.as_deref());
+    std::iter::once(card.type_line.as_str())
+        .chain(faces)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The parsed mana costs of the card or of its faces.
+fn mana_costs(card: &Card) -> impl Iterator<Item = ManaCost> + '_ {
+    let costs: Vec<&str> = match &card.mana_cost {
+        Some(cost) => cost.split(" // ").collect(),
+        None => faces(card).map(|face| face.mana_cost.as_str()).collect(),
+    };
+    costs.into_iter().filter_map(|cost| cost.parse().ok())
+}
+
+fn card_colors(card: &Card) -> Colors {
+    match &card.colors {
+        Some(colors) => Colors::from_slice(colors),
+        None => faces(card)
+            .flat_map(|face| face.colors.iter().flatten())
+            .fold(Colors::colorless(), |colors, color| colors.with(*color)),
+    }
+}
+
+fn color_count(colors: Colors) -> usize {
+    [
+        Color::White,
+        Color::Blue,
+        Color::Black,
+        Color::Red,
+        Color::Green,
+    ]
+    .iter()
+    .filter(|color| colors.is(**color))
+    .count()
+}
+
+/// Parses color letters like `bg`, or names of colors and their
+/// combinations like `golgari`.
+fn parse_colors(value: &str) -> Option<Colors> {
+    let colors = match value {
+        "white" => Colors::WHITE,
+        "blue" => Colors::BLUE,
+        "black" => Colors::BLACK,
+        "red" => Colors::RED,
+        "green" => Colors::GREEN,
+        "colorless" => Colors::COLORLESS,
+        "azorius" => Colors::AZORIUS,
+        "dimir" => Colors::DIMIR,
+        "rakdos" => Colors::RAKDOS,
+        "gruul" => Colors::GRUUL,
+        "selesnya" => Colors::SELESNYA,
+        "orzhov" => Colors::ORZHOV,
+        "izzet" => Colors::IZZET,
+        "golgari" => Colors::GOLGARI,
+        "boros" => Colors::BOROS,
+        "simic" => Colors::SIMIC,
+        "esper" => Colors::ESPER,
+        "grixis" => Colors::GRIXIS,
+        "jund" => Colors::JUND,
+        "naya" => Colors::NAYA,
+        "bant" => Colors::BANT,
+        "abzan" => Colors::ABZAN,
+        "jeskai" => Colors::JESKAI,
+        "sultai" => Colors::SULTAI,
+        "mardu" => Colors::MARDU,
+        "temur" => Colors::TEMUR,
+        _ => {
+            return value
+                .chars()
+                .try_fold(Colors::colorless(), |colors, letter| {
+                    Some(colors.with(match letter {
+                        'w' => Color::White,
+                        'u' => Color::Blue,
+                        'b' => Color::Black,
+                        'r' => Color::Red,
+                        'g' => Color::Green,
+                        'c' => Color::Colorless,
+                        _ => return None,
+                    }))
+                })
+        },
+    };
+    Some(colors)
+}
+
+/// Compares the colors of a card with a color value or count. Without an
+/// operator, `default` is used, except for colorless which has to be equal.
+fn compare_colors(
+    card: Colors,
+    op: Option<CompareOp>,
+    value: &str,
+    default: CompareOp,
+) -> Option<bool> {
+    let value = unquote(value).to_lowercase();
+    if let Ok(count) = value.parse::<usize>() {
+        return Some(
+            op.unwrap_or(CompareOp::Eq)
+                .matches(color_count(card).partial_cmp(&count)),
+        );
+    }
+    if matches!(value.as_str(), "m" | "multicolor") {
+        return match op {
+            None | Some(CompareOp::Eq) => Some(card.is_multicolored()),
+            _ => None,
+        };
+    }
+    let colors = parse_colors(&value)?;
+    let is_subset = |a: Colors, b: Colors| a.difference(b).is_colorless();
+    Some(
+        match op.unwrap_or(if colors.is_colorless() {
+            CompareOp::Eq
+        } else {
+            default
+        }) {
+            CompareOp::Eq => card == colors,
+            CompareOp::Neq => card != colors,
+            CompareOp::Gte => is_subset(colors, card),
+            CompareOp::Gt => is_subset(colors, card) && card != colors,
+            CompareOp::Lte => is_subset(card, colors),
+            CompareOp::Lt => is_subset(card, colors) && card != colors,
+        },
+    )
+}
+
+fn legality(
+    op: Option<CompareOp>,
+    value: &str,
+    card: &Card,
+    legalities: &[Legality],
+) -> Option<bool> {
+    let format: Format = parse_enum(&text(op, value)?)?;
+    Some(
+        card.legalities
+            .get(&format)
+            .map_or(false, |legality| legalities.contains(legality)),
+    )
+}
+
+/// Parses the properties a numeric property can be compared against locally.
+fn parse_num_property(value: &str) -> Option<NumProperty> {
+    Some(match value {
+        "power" => NumProperty::Power,
+        "toughness" => NumProperty::Toughness,
+        "powtou" => NumProperty::PowTou,
+        "loyalty" => NumProperty::Loyalty,
+        "cmc" => NumProperty::Cmc,
+        "usd" => NumProperty::Usd,
+        "usdfoil" => NumProperty::UsdFoil,
+        "eur" => NumProperty::Eur,
+        "tix" => NumProperty::Tix,
+        "year" => NumProperty::Year,
+        _ => return None,
+    })
+}
+
+/// The value of a numeric property of the card, `Some(None)` if the card
+/// doesn't have it, like a noncreature's power, or `None` if it can't be
+/// evaluated locally.
+fn numeric_property(card: &Card, property: NumProperty) -> Option<Option<f64>> {
+    let stat = |card_stat: &Option<String>,
+                face_stat: fn(&crate::card::CardFace) -> &Option<String>| {
+        card_stat
+            .as_deref()
+            .or_else(|| faces(card).find_map(|face| face_stat(face).as_deref()))
+            .map(parse_stat)
+    };
+    let price = |price: &Option<String>| price.as_deref().and_then(|price| price.parse().ok());
+    Some(match property {
+        NumProperty::Power => stat(&card.power, |face| &face.power),
+        NumProperty::Toughness => stat(&card.toughness, |face| &face.toughness),
+        NumProperty::PowTou => stat(&card.power, |face| &face.power)
+            .zip(stat(&card.toughness, |face| &face.toughness))
+            .map(|(power, toughness)| power + toughness),
+        NumProperty::Loyalty => stat(&card.loyalty, |face| &face.loyalty),
+        NumProperty::Cmc => Some(f64::from(card.cmc)),
+        NumProperty::Usd => price(&card.prices.usd),
+        NumProperty::UsdFoil => price(&card.prices.usd_foil),
+        NumProperty::Eur => price(&card.prices.eur),
+        NumProperty::Tix => price(&card.prices.tix),
+        NumProperty::Year => Some(f64::from(card.released_at.year())),
+        _ => return None,
+    })
+}
+
+/// Parses power, toughness or loyalty, counting `*` and `X` as 0, so `1+*`
+/// is 1.
+fn parse_stat(stat: &str) -> f64 {
+    let number: String = stat
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || matches!(c, '-' | '.'))
+        .collect();
+    number.parse().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use crate::card::{Card, Color, Colors, Multicolored, Rarity};
+    use crate::format::Format;
+    use crate::search::prelude::*;
+
+    /// A card with a split identity, a variable power and two keywords.
+    pub(crate) fn abomination() -> Card {
+        serde_json::from_str(
+            r#"{
+            "object": "card",
+            "id": "d4b8d8de-fb54-4b6d-9f58-9a1a2ec4d2d8",
+            "oracle_id": "55e4e4ea-7a0c-4ba8-a6fa-0a14ae8e0a5c",
+            "name": "Abomination of Llanowar",
+            "lang": "en",
+            "released_at": "2021-02-05",
+            "uri": "https://api.scryfall.com/cards/d4b8d8de-fb54-4b6d-9f58-9a1a2ec4d2d8",
+            "scryfall_uri": "https://scryfall.com/card/khc/74/abomination-of-llanowar",
+            "layout": "normal",
+            "highres_image": true,
+            "image_uris": {},
+            "mana_cost": "{1}{B}{G}",
+            "cmc": 3.0,
+            "type_line": "Legendary Creature — Elf Horror",
+            "oracle_text": "Vigilance; menace\nAbomination of Llanowar's power is the number of Elves.",
+            "power": "*",
+            "toughness": "1+*",
+            "colors": ["B", "G"],
+            "color_identity": ["B", "G"],
+            "keywords": ["Vigilance", "Menace"],
+            "legalities": {"commander": "legal", "modern": "not_legal", "paupercommander": "restricted"},
+            "games": ["paper"],
+            "reserved": false,
+            "foil": false,
+            "nonfoil": true,
+            "oversized": false,
+            "promo": false,
+            "reprint": true,
+            "variation": false,
+            "set": "khc",
+            "set_name": "Kaldheim Commander",
+            "set_type": "commander",
+            "set_uri": "https://api.scryfall.com/sets/khc",
+            "set_search_uri": "https://api.scryfall.com/cards/search?q=e%3Akhc",
+            "scryfall_set_uri": "https://scryfall.com/sets/khc",
+            "rulings_uri": "https://api.scryfall.com/cards/d4b8d8de/rulings",
+            "prints_search_uri": "https://api.scryfall.com/cards/search?q=oracleid",
+            "collector_number": "74",
+            "digital": false,
+            "rarity": "uncommon",
+            "artist": "Vincent Proce",
+            "border_color": "black",
+            "frame": "2015",
+            "full_art": false,
+            "textless": false,
+            "booster": false,
+            "story_spotlight": false,
+            "prices": {"usd": "0.10", "usd_foil": null, "eur": "0.05", "eur_foil": null, "tix": null},
+            "related_uris": {},
+            "purchase_uris": {}
+        }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn text_and_colors() {
+        let card = abomination();
+        let matching = [
+            name("llanowar"),
+            exact("Abomination of Llanowar"),
+            type_line("elf"),
+            oracle_text("~'s power"),
+            keyword("menace"),
+            color("g"),
+            color("golgari"),
+            color(eq("bg")),
+            color(gt(Color::Black)),
+            color(Multicolored),
+            color_count(2),
+            color_identity("bgw"),
+            color_identity(lte("jund")),
+            set("khc"),
+            artist("proce"),
+        ];
+        for query in &matching {
+            assert!(query.matches(&card).unwrap(), "{}", query);
+        }
+        let not_matching = [
+            type_line("land"),
+            color("w"),
+            color("c"),
+            color(lt("bg")),
+            color_identity("g"),
+            color_identity(Colors::colorless()),
+            set("khm"),
+        ];
+        for query in &not_matching {
+            assert!(!query.matches(&card).unwrap(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn numbers_rarity_and_formats() {
+        let card = abomination();
+        let matching = [
+            cmc(3),
+            cmc(gte(2.5)),
+            power(0),
+            toughness(1),
+            toughness(gt(NumProperty::Power)),
+            pow_tou(1),
+            usd(lt(1)),
+            eur(0.05),
+            year(2021),
+            rarity(Rarity::Uncommon),
+            rarity(gt(Rarity::Common)),
+            rarity("u"),
+            format(Format::Commander),
+            format("paupercommander"),
+            restricted(Format::PauperCommander),
+            date(gte("2021-01-01")),
+        ];
+        for query in &matching {
+            assert!(query.matches(&card).unwrap(), "{}", query);
+        }
+        let not_matching = [
+            cmc(lt(3)),
+            loyalty(0),
+            tix(0),
+            rarity(gte(Rarity::Rare)),
+            format(Format::Modern),
+            banned(Format::Commander),
+        ];
+        for query in &not_matching {
+            assert!(!query.matches(&card).unwrap(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn boolean_operations_and_criteria() {
+        let card = abomination();
+        let query = type_line("creature")
+            .and(Query::Or(vec![color("w").into(), color("b").into()]))
+            .and(not(CardIs::Reserved))
+            .and(CardIs::Commander)
+            .and(CardIs::OddCmc)
+            .and(PrintingIs::Reprint);
+        assert!(query.matches(&card).unwrap());
+        assert!(!not(query).matches(&card).unwrap());
+        assert!(!Query::Or(vec![]).matches(&card).unwrap());
+        assert!(Query::default().matches(&card).unwrap());
+        assert!(gte(5).matches(&7) && !lt(5).matches(&7));
+    }
+
+    #[test]
+    fn unsupported_parameters() {
+        let card = abomination();
+        for query in [
+            name(Regex::from("^abom")),
+            in_set("khm"),
+            cube("vintage"),
+            print_count(2),
+            CardIs::ShockLand.into(),
+            type_line("elf").and(Query::Custom("t:horror".to_string())),
+            Query::Or(vec![color("b"), PrintingIs::NewArt.into()]),
+        ] {
+            assert!(query.matches(&card).is_err(), "{}", query);
+            assert!(query.check_local().is_err(), "{}", query);
+        }
+        for query in [type_line("elf"), usd(lt(1)), CardIs::GameChanger.into()] {
+            assert!(query.check_local().is_ok(), "{}", query);
+        }
+    }
+}
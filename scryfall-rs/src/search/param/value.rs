@@ -20,7 +20,7 @@ use crate::search::param::Param;
 /// Refer to [the syntax documentation](https://scryfall.com/docs/syntax) for details on the
 /// available parameter types.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-pub struct ValueKind(ValueKindImpl);
+pub struct ValueKind(pub(super) ValueKindImpl);
 
 impl ValueKind {
     pub(super) fn fmt_value(&self, value: &str, f: &mut fmt::Formatter) -> fmt::Result {
@@ -37,8 +37,89 @@ impl ValueKind {
     }
 }
 
+impl ValueKind {
+    /// Finds the kind of parameter written as `key:value`, accepting the
+    /// abbreviations Scryfall does. The value is only needed to tell apart
+    /// the meanings of `in:`, which defaults to a set.
+    pub(in crate::search) fn parse(key: &str, value: &str) -> Option<ValueKind> {
+        let kind = match key {
+            "c" | "color" => ValueKindImpl::Color,
+            "id" | "identity" => ValueKindImpl::ColorIdentity,
+            "t" | "type" => ValueKindImpl::Type,
+            "o" | "oracle" => ValueKindImpl::Oracle,
+            "fo" | "fulloracle" => ValueKindImpl::FullOracle,
+            "kw" | "keyword" => ValueKindImpl::Keyword,
+            "m" | "mana" => ValueKindImpl::Mana,
+            "devotion" => ValueKindImpl::Devotion,
+            "produces" => ValueKindImpl::Produces,
+            "r" | "rarity" => ValueKindImpl::Rarity,
+            "s" | "e" | "set" | "edition" => ValueKindImpl::Set,
+            "cn" | "number" => ValueKindImpl::Number,
+            "b" | "block" => ValueKindImpl::Block,
+            "st" | "settype" => ValueKindImpl::SetType,
+            "cube" => ValueKindImpl::Cube,
+            "f" | "format" | "legal" => ValueKindImpl::Format,
+            "banned" => ValueKindImpl::Banned,
+            "restricted" => ValueKindImpl::Restricted,
+            "cheapest" => ValueKindImpl::Cheapest,
+            "a" | "artist" => ValueKindImpl::Artist,
+            "ft" | "flavor" => ValueKindImpl::Flavor,
+            "wm" | "watermark" => ValueKindImpl::Watermark,
+            "border" => ValueKindImpl::BorderColor,
+            "frame" => ValueKindImpl::Frame,
+            "date" => ValueKindImpl::Date,
+            "game" => ValueKindImpl::Game,
+            "lang" | "language" => ValueKindImpl::Language,
+            "name" => ValueKindImpl::Name,
+            "in" => in_kind(value),
+            "pow" | "power" => ValueKindImpl::NumericComparable(NumProperty::Power),
+            "tou" | "toughness" => ValueKindImpl::NumericComparable(NumProperty::Toughness),
+            "pt" | "powtou" => ValueKindImpl::NumericComparable(NumProperty::PowTou),
+            "loy" | "loyalty" => ValueKindImpl::NumericComparable(NumProperty::Loyalty),
+            "cmc" | "mv" | "manavalue" => ValueKindImpl::NumericComparable(NumProperty::Cmc),
+            "artists" => ValueKindImpl::NumericComparable(NumProperty::ArtistCount),
+            "usd" => ValueKindImpl::NumericComparable(NumProperty::Usd),
+            "usdfoil" => ValueKindImpl::NumericComparable(NumProperty::UsdFoil),
+            "eur" => ValueKindImpl::NumericComparable(NumProperty::Eur),
+            "tix" => ValueKindImpl::NumericComparable(NumProperty::Tix),
+            "illustrations" => ValueKindImpl::NumericComparable(NumProperty::IllustrationCount),
+            "prints" => ValueKindImpl::NumericComparable(NumProperty::PrintCount),
+            "sets" => ValueKindImpl::NumericComparable(NumProperty::SetCount),
+            "paperprints" => ValueKindImpl::NumericComparable(NumProperty::PaperPrintCount),
+            "papersets" => ValueKindImpl::NumericComparable(NumProperty::PaperSetCount),
+            "year" => ValueKindImpl::NumericComparable(NumProperty::Year),
+            _ => return None,
+        };
+        Some(ValueKind(kind))
+    }
+}
+
+/// The language codes Scryfall uses, for telling `in:ja` from a set.
+const LANGUAGES: [&str; 19] = [
+    "en", "es", "fr", "de", "it", "pt", "ja", "ko", "ru", "zhs", "zht", "he", "la", "grc", "ar",
+    "sa", "ph", "px", "qya",
+];
+
+/// What `in:value` searches for: a rarity, game, set type or language the
+/// card has been printed in, or otherwise a set.
+fn in_kind(value: &str) -> ValueKindImpl {
+    let value = value.to_lowercase();
+    let json = || serde_json::Value::String(value.clone());
+    if serde_json::from_value::<crate::card::Rarity>(json()).is_ok() {
+        ValueKindImpl::InRarity
+    } else if serde_json::from_value::<crate::card::Game>(json()).is_ok() {
+        ValueKindImpl::InGame
+    } else if serde_json::from_value::<crate::set::SetType>(json()).is_ok() {
+        ValueKindImpl::InSetType
+    } else if LANGUAGES.contains(&value.as_str()) {
+        ValueKindImpl::InLanguage
+    } else {
+        ValueKindImpl::InSet
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-enum ValueKindImpl {
+pub(super) enum ValueKindImpl {
     Color,
     ColorIdentity,
     Type,
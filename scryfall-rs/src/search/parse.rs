@@ -0,0 +1,402 @@
+//! This module parses [Scryfall syntax](https://scryfall.com/docs/syntax) into
+//! a [`Query`], so searches written the way they are on scryfall.com can be
+//! [evaluated locally][Query::matches] or checked before they're sent.
+//!
+//! Parsing is done through [`FromStr`], and failures are reported as a
+//! [`ParseError`] pointing at the offending character.
+//!
+//! # Examples
+//! ```rust
+//! use scryfall::search::prelude::*;
+//!
+//! let query: Query = "t:elf -is:funny (o:sacrifice OR cmc>=3)".parse().unwrap();
+//! assert_eq!(
+//!     query.to_string(),
+//!     "(type:elf AND -is:funny AND (oracle:sacrifice OR cmc>=3))"
+//! );
+//!
+//! let error = "t:elf (o:sacrifice".parse::<Query>().unwrap_err();
+//! assert_eq!(error.position, 6);
+//! ```
+use std::str::FromStr;
+
+use crate::search::param::compare::CompareOp;
+use crate::search::param::criteria::Criterion;
+use crate::search::param::value::ValueKind;
+use crate::search::param::Param;
+use crate::search::query::{not, Query};
+
+/// A syntax error in a search query.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message} at position {position}")]
+pub struct ParseError {
+    /// The position in the query, in characters, where the error is.
+    pub position: usize,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    /// Parses a query in Scryfall syntax. Search terms next to each other
+    /// must all match, `OR` matches either side and binds looser than that,
+    /// `-` negates a term and parentheses group terms. Text values can be
+    /// quoted or, where Scryfall allows it, `/regular expressions/`.
+    ///
+    /// Values are kept as they're written, so a parsed query is sent to
+    /// Scryfall the same way. A query without any terms parses as the empty
+    /// [`Query::default`].
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            chars: query.chars().collect(),
+            position: 0,
+        };
+        parser.skip_whitespace();
+        if parser.peek().is_none() {
+            return Ok(Query::default());
+        }
+        let query = parser.or()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some(')') => Err(parser.error("Unexpected `)`")),
+            Some(_) => Err(parser.error("Expected a search term")),
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            position: self.position,
+            message: message.into(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map_or(false, char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    /// Whether the keyword `OR` or `AND`, in any case, is next.
+    fn at_keyword(&self, keyword: &str) -> bool {
+        let len = keyword.chars().count();
+        let word: String = self.chars.iter().skip(self.position).take(len).collect();
+        let ends = match self.peek_at(len) {
+            None | Some('(') => true,
+            Some(c) => c.is_whitespace(),
+        };
+        ends && word.eq_ignore_ascii_case(keyword)
+    }
+
+    /// Skips the keyword `OR` or `AND` if it's next.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = self.at_keyword(keyword);
+        if found {
+            self.position += keyword.len();
+            self.skip_whitespace();
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Query, ParseError> {
+        let mut queries = vec![self.and()?];
+        while self.keyword("OR") {
+            queries.push(self.and()?);
+        }
+        Ok(group(queries, Query::Or))
+    }
+
+    fn and(&mut self) -> Result<Query, ParseError> {
+        let mut queries = vec![self.unary()?];
+        loop {
+            match self.peek() {
+                None | Some(')') => break,
+                _ if self.at_keyword("OR") => break,
+                _ => {
+                    self.keyword("AND");
+                    queries.push(self.unary()?);
+                },
+            }
+        }
+        Ok(group(queries, Query::And))
+    }
+
+    fn unary(&mut self) -> Result<Query, ParseError> {
+        let query = if self.peek() == Some('-') {
+            self.position += 1;
+            not(self.atom()?)
+        } else {
+            self.atom()?
+        };
+        self.skip_whitespace();
+        Ok(query)
+    }
+
+    fn atom(&mut self) -> Result<Query, ParseError> {
+        match self.peek() {
+            Some('(') => {
+                let open = self.position;
+                self.position += 1;
+                self.skip_whitespace();
+                let query = self.or()?;
+                if self.peek() == Some(')') {
+                    self.position += 1;
+                    Ok(query)
+                } else {
+                    self.position = open;
+                    Err(self.error("Unclosed `(`"))
+                }
+            },
+            Some(')') => Err(self.error("Unexpected `)`")),
+            None => Err(self.error("Expected a search term")),
+            Some(c) if c.is_whitespace() || c == '-' => Err(self.error("Expected a search term")),
+            Some(_) => self.term(),
+        }
+    }
+
+    fn term(&mut self) -> Result<Query, ParseError> {
+        if self.peek() == Some('!') {
+            self.position += 1;
+            let name = self.value()?;
+            return Ok(Query::Param(Param::exact(unquote(&name))));
+        }
+
+        let start = self.position;
+        while self
+            .peek()
+            .map_or(false, |c| c.is_alphanumeric() || c == '_')
+        {
+            self.position += 1;
+        }
+        let key: String = self.chars[start..self.position].iter().collect();
+        let op = match (self.peek(), self.peek_at(1)) {
+            _ if key.is_empty() => None,
+            (Some(':'), _) => Some((1, None)),
+            (Some('!'), Some('=')) => Some((2, Some(CompareOp::Neq))),
+            (Some('<'), Some('=')) => Some((2, Some(CompareOp::Lte))),
+            (Some('>'), Some('=')) => Some((2, Some(CompareOp::Gte))),
+            (Some('<'), _) => Some((1, Some(CompareOp::Lt))),
+            (Some('>'), _) => Some((1, Some(CompareOp::Gt))),
+            (Some('='), _) => Some((1, Some(CompareOp::Eq))),
+            _ => None,
+        };
+        let (len, op) = match op {
+            Some(op) => op,
+            None => {
+                // Not a `key:value` term, so it's part of the card name.
+                self.position = start;
+                let name = self.value()?;
+                let kind = ValueKind::parse("name", &name).expect("name is a search keyword");
+                return Ok(Query::Param(Param::value(kind, name)));
+            },
+        };
+        let op_position = self.position;
+        self.position += len;
+        let value_position = self.position;
+        let value = self.value()?;
+
+        let key = key.to_lowercase();
+        if let Some(criterion) = criterion(&key, op, &value, op_position, value_position)? {
+            return Ok(criterion);
+        }
+        let kind = match ValueKind::parse(&key, &unquote(&value)) {
+            Some(kind) => kind,
+            None => {
+                return Err(ParseError {
+                    position: start,
+                    message: format!("Unknown search keyword `{}`", key),
+                })
+            },
+        };
+        Ok(Query::Param(match op {
+            None => Param::value(kind, value),
+            Some(op) => Param::comparison(kind, op, value),
+        }))
+    }
+
+    /// Reads a value: a quoted string, a `/regular expression/` or a word up
+    /// to the next space or `)`. It's returned as it's written, quotes and
+    /// all.
+    fn value(&mut self) -> Result<String, ParseError> {
+        let start = self.position;
+        match self.peek() {
+            Some(delimiter @ ('"' | '/')) => {
+                self.position += 1;
+                loop {
+                    match self.peek() {
+                        None => {
+                            self.position = start;
+                            return Err(self.error(if delimiter == '"' {
+                                "Unclosed `\"`"
+                            } else {
+                                "Unclosed regular expression"
+                            }));
+                        },
+                        Some('\\') if delimiter == '/' => self.position += 2,
+                        Some(c) if c == delimiter => {
+                            self.position += 1;
+                            break;
+                        },
+                        Some(_) => self.position += 1,
+                    }
+                }
+            },
+            _ => {
+                while self
+                    .peek()
+                    .map_or(false, |c| !c.is_whitespace() && c != ')')
+                {
+                    self.position += 1;
+                }
+                if self.position == start {
+                    return Err(self.error("Missing value"));
+                }
+            },
+        }
+        self.position = self.position.min(self.chars.len());
+        Ok(self.chars[start..self.position].iter().collect())
+    }
+}
+
+/// Parses the criteria like `is:commander` and `not:commander`, and
+/// `cmc:even`, which is otherwise a number.
+fn criterion(
+    key: &str,
+    op: Option<CompareOp>,
+    value: &str,
+    op_position: usize,
+    value_position: usize,
+) -> Result<Option<Query>, ParseError> {
+    let value = value.to_lowercase();
+    if key == "cmc" && op.is_none() {
+        return Ok(Criterion::parse(key, &value).map(|criterion| criterion.into()));
+    }
+    if !matches!(key, "is" | "has" | "not" | "new") {
+        return Ok(None);
+    }
+    if op.is_some() {
+        return Err(ParseError {
+            position: op_position,
+            message: format!("`{}:` can't be compared", key),
+        });
+    }
+    match Criterion::parse(key, &value) {
+        Some(criterion) if key == "not" => Ok(Some(not(criterion))),
+        Some(criterion) => Ok(Some(criterion.into())),
+        None => Err(ParseError {
+            position: value_position,
+            message: format!("Unknown criterion `{}:{}`", key, value),
+        }),
+    }
+}
+
+/// A single query as it is, several combined with `combine`.
+fn group(mut queries: Vec<Query>, combine: fn(Vec<Query>) -> Query) -> Query {
+    if queries.len() == 1 {
+        queries.remove(0)
+    } else {
+        combine(queries)
+    }
+}
+
+fn unquote(value: &str) -> String {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::param::abomination;
+    use crate::search::prelude::*;
+
+    fn parse(query: &str) -> Query {
+        query.parse().unwrap()
+    }
+
+    fn error(query: &str) -> ParseError {
+        query.parse::<Query>().unwrap_err()
+    }
+
+    #[test]
+    fn parse_terms() {
+        let query = parse(r#"t:elf o:"sacrifice" id<=bg cmc>=3 -is:funny (a OR b)"#);
+        assert_eq!(
+            query,
+            Query::And(vec![
+                Param::value(ValueKind::parse("t", "").unwrap(), "elf").into(),
+                Param::value(ValueKind::parse("o", "").unwrap(), "\"sacrifice\"").into(),
+                Param::comparison(ValueKind::parse("id", "").unwrap(), CompareOp::Lte, "bg").into(),
+                cmc(gte(3)),
+                not(CardIs::Funny),
+                Query::Or(vec![parse("name:a"), parse("name:b")]),
+            ])
+        );
+        assert_eq!(
+            query.to_string(),
+            r#"(type:elf AND oracle:"sacrifice" AND identity<=bg AND cmc>=3 AND -is:funny AND (name:a OR name:b))"#
+        );
+        assert_eq!(parse(&query.to_string()), query);
+    }
+
+    #[test]
+    fn parse_special_terms() {
+        assert_eq!(parse(r#"!"Lightning Bolt""#), exact("Lightning Bolt"));
+        assert_eq!(parse("has:watermark"), PrintingIs::Watermark.into());
+        assert_eq!(parse("not:reprint"), not(PrintingIs::Reprint));
+        assert_eq!(parse("cmc:even"), CardIs::EvenCmc.into());
+        assert_eq!(parse("in:rare"), in_rarity(crate::card::Rarity::Rare));
+        assert_eq!(parse("in:arena"), in_game(crate::card::Game::Arena));
+        assert_eq!(parse("in:ja").to_string(), "in:ja");
+        assert_eq!(parse("o:/^{T}:/").to_string(), "oracle:/^{T}:/");
+        assert_eq!(parse("  "), Query::default());
+        assert_eq!(
+            parse("a and b or c"),
+            Query::Or(vec![Query::And(vec![parse("a"), parse("b")]), parse("c")])
+        );
+    }
+
+    #[test]
+    fn error_positions() {
+        assert_eq!(error("t:elf (o:sacrifice").position, 6);
+        assert_eq!(error(r#"t:elf o:"sacrifice"#).position, 8);
+        assert_eq!(error("t:elf o:/sacrifice").position, 8);
+        assert_eq!(error("t:elf o:sacrifice)").position, 17);
+        assert_eq!(error("t:elf xyz:1").position, 6);
+        assert_eq!(error("is:brokenn").position, 3);
+        assert_eq!(error("is>commander").position, 2);
+        assert_eq!(error("t: elf").position, 2);
+        assert_eq!(error("a OR").position, 4);
+        assert_eq!(error("- a").position, 1);
+        assert_eq!(
+            error("t:elf xyz:1").to_string(),
+            "Unknown search keyword `xyz` at position 6"
+        );
+    }
+
+    #[test]
+    fn parse_and_match() {
+        let card = abomination();
+        assert!(parse(r#"t:elf o:"menace" id<=bg"#).matches(&card).unwrap());
+        assert!(parse("c:g (cmc>=3 OR tou=1)").matches(&card).unwrap());
+        assert!(!parse("t:elf -c:g").matches(&card).unwrap());
+    }
+}
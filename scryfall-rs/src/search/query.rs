@@ -5,7 +5,9 @@ use std::fmt;
 
 use url::Url;
 
-use crate::search::param::Param;
+use crate::card::Card;
+use crate::error::Error;
+use crate::search::param::{blank_card, Param};
 use crate::search::Search;
 
 /// A search query, composed of search parameters and boolean operations.
@@ -119,6 +121,54 @@ macro_rules! impl_and_or {
 }
 
 impl Query {
+    /// Whether `card` matches this query, evaluated locally instead of by
+    /// Scryfall, e.g. to filter cards stored offline. Fails if any of the
+    /// parameters can't be evaluated locally, like [`Custom`][Query::Custom]
+    /// queries. See [`Param::matches`] for the supported parameters.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// # use scryfall::search::prelude::*;
+    /// # fn main() -> scryfall::Result<()> {
+    /// let bolt = scryfall::Card::named("Lightning Bolt")?;
+    /// let query = type_line("instant").and(color_identity("r")).and(not(cmc(gt(1))));
+    /// assert!(query.matches(&bolt)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn matches(&self, card: &Card) -> crate::Result<bool> {
+        // Every sub-query is evaluated, so unsupported parameters fail for all
+        // cards alike.
+        match self {
+            Query::And(queries) => queries
+                .iter()
+                .try_fold(true, |all, query| Ok(query.matches(card)? && all)),
+            Query::Or(queries) => queries
+                .iter()
+                .try_fold(false, |any, query| Ok(query.matches(card)? || any)),
+            Query::Not(query) => Ok(!query.matches(card)?),
+            Query::Param(param) => param.matches(card),
+            Query::Custom(query) => Err(Error::Other(format!(
+                "({}) can't be evaluated locally",
+                query
+            ))),
+        }
+    }
+
+    /// Fails like [`matches`][Query::matches] if the query can't be evaluated
+    /// locally. That doesn't depend on the card, so a query can be checked once
+    /// before matching it against many cards.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use scryfall::search::prelude::*;
+    /// assert!(type_line("elf").check_local().is_ok());
+    /// assert!(Query::Custom("in:lea".to_string()).check_local().is_err());
+    /// ```
+    pub fn check_local(&self) -> crate::Result<()> {
+        self.matches(&blank_card()).map(|_| ())
+    }
+
     impl_and_or! {
         #[doc = "Combines `self` with `other` using the boolean AND operation."]
         and(And),
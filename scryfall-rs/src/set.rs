@@ -20,7 +20,7 @@ pub use self::set_type::SetType;
 use crate::card::Card;
 use crate::list::{List, ListIter};
 use crate::uri::Uri;
-use crate::util::SETS_URL;
+use crate::util::sets_url;
 
 /// A Set object containing all fields that `scryfall` provides.
 ///
@@ -102,7 +102,7 @@ impl Set {
     /// assert!(sets.len() > 0);
     /// ```
     pub fn all() -> crate::Result<ListIter<Set>> {
-        let mut url = SETS_URL.clone();
+        let mut url = sets_url();
         url.query_pairs_mut().append_pair("page", "1");
         Uri::from(url).fetch_iter()
     }
@@ -117,7 +117,7 @@ impl Set {
     /// assert_eq!(Set::code("mmq").unwrap().name, "Mercadian Masques")
     /// ```
     pub fn code(code: &str) -> crate::Result<Set> {
-        Uri::from(SETS_URL.join(&percent_encode(code.as_bytes(), NON_ALPHANUMERIC).to_string())?)
+        Uri::from(sets_url().join(&percent_encode(code.as_bytes(), NON_ALPHANUMERIC).to_string())?)
             .fetch()
     }
 
@@ -133,7 +133,7 @@ impl Set {
     /// ```
     pub fn tcgplayer<T: std::fmt::Display>(code: T) -> crate::Result<Set> {
         Uri::from(
-            SETS_URL
+            sets_url()
                 .join("tcgplayer/")?
                 .join(&percent_encode(code.to_string().as_bytes(), NON_ALPHANUMERIC).to_string())?,
         )
@@ -153,7 +153,7 @@ impl Set {
     /// )
     /// ```
     pub fn uuid(uuid: Uuid) -> crate::Result<Set> {
-        Uri::from(SETS_URL.join(&uuid.to_string())?).fetch()
+        Uri::from(sets_url().join(&uuid.to_string())?).fetch()
     }
 
     /// Returns an iterator over the cards of the set.
@@ -0,0 +1,174 @@
+//! Card symbols are the symbols that appear in mana costs and card text, like
+//! `{T}` or `{G/P}`, and the endpoint parsing mana costs into their canonical
+//! form.
+//!
+//! Visit the official [docs](https://scryfall.com/docs/api/card-symbols) for more documentation.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::card::Color;
+use crate::list::{List, ListIter};
+use crate::uri::Uri;
+use crate::util::symbology_url;
+
+/// A symbol that may appear in a mana cost or in the text of a card.
+///
+/// For more information, refer to the [official docs](https://scryfall.com/docs/api/card-symbols).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct CardSymbol {
+    /// The plaintext symbol, like `{G/P}`.
+    pub symbol: String,
+
+    /// An alternate version of the symbol that is sometimes used when the
+    /// braces are left out, like `G/P`.
+    pub loose_variant: Option<String>,
+
+    /// An English snippet that describes this symbol.
+    pub english: String,
+
+    /// True if it is possible to write this symbol “backwards”, like `{P/G}`.
+    pub transposable: bool,
+
+    /// True if this is a mana symbol.
+    pub represents_mana: bool,
+
+    /// The mana value of this symbol, if it represents mana. Half mana
+    /// symbols have a value of 0.5.
+    pub cmc: Option<f32>,
+
+    /// True if this symbol appears in a mana cost on any Magic card.
+    pub appears_in_mana_costs: bool,
+
+    /// True if this symbol is only used on funny cards or Un-cards.
+    pub funny: bool,
+
+    /// The colors of this symbol.
+    pub colors: Vec<Color>,
+
+    /// True if this is a hybrid mana symbol, like `{G/W}` or `{2/W}`.
+    #[serde(default)]
+    pub hybrid: bool,
+
+    /// True if this is a Phyrexian mana symbol, like `{G/P}`.
+    #[serde(default)]
+    pub phyrexian: bool,
+
+    /// Alternate ways this symbol was printed by Gatherer.
+    pub gatherer_alternates: Option<Vec<String>>,
+
+    /// A link to an SVG image of this symbol.
+    pub svg_uri: Option<Url>,
+}
+
+/// A mana cost parsed by [`CardSymbol::parse_mana`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ParsedManaCost {
+    /// The canonical form of the mana cost, like `{X}{2}{G}{G}`.
+    pub cost: String,
+
+    /// The mana value of the cost.
+    pub cmc: f32,
+
+    /// The colors of the cost.
+    pub colors: Vec<Color>,
+
+    /// True if the cost is colorless.
+    pub colorless: bool,
+
+    /// True if the cost is of exactly one color.
+    pub monocolored: bool,
+
+    /// True if the cost is of more than one color.
+    pub multicolored: bool,
+}
+
+impl CardSymbol {
+    /// Returns all card symbols.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use scryfall::symbology::CardSymbol;
+    /// assert!(
+    ///     CardSymbol::all()
+    ///         .unwrap()
+    ///         .map(Result::unwrap)
+    ///         .any(|symbol| symbol.symbol == "{G/P}" && symbol.phyrexian)
+    /// );
+    /// ```
+    pub fn all() -> crate::Result<ListIter<Self>> {
+        Uri::<List<Self>>::from(symbology_url()).fetch_iter()
+    }
+
+    /// Parses a mana cost, written in any of the ways Scryfall understands,
+    /// into its canonical form.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use scryfall::card::Color;
+    /// use scryfall::symbology::CardSymbol;
+    /// let cost = CardSymbol::parse_mana("RUx").unwrap();
+    /// assert_eq!(cost.cost, "{X}{U}{R}");
+    /// assert_eq!(cost.cmc, 2.0);
+    /// assert_eq!(cost.colors, [Color::Blue, Color::Red]);
+    /// assert!(cost.multicolored);
+    /// ```
+    pub fn parse_mana(cost: &str) -> crate::Result<ParsedManaCost> {
+        let mut url = symbology_url().join("parse-mana")?;
+        url.query_pairs_mut().append_pair("cost", cost);
+        Uri::from(url).fetch()
+    }
+}
+
+#[cfg(feature = "async")]
+impl CardSymbol {
+    /// Returns all card symbols like [`all`][CardSymbol::all], without
+    /// blocking the thread.
+    pub async fn all_async() -> crate::Result<Vec<Self>> {
+        Uri::<List<Self>>::from(symbology_url())
+            .fetch_all_async()
+            .await
+    }
+
+    /// Parses a mana cost like [`parse_mana`][CardSymbol::parse_mana],
+    /// without blocking the thread.
+    pub async fn parse_mana_async(cost: &str) -> crate::Result<ParsedManaCost> {
+        let mut url = symbology_url().join("parse-mana")?;
+        url.query_pairs_mut().append_pair("cost", cost);
+        Uri::from(url).fetch_async().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use super::*;
+
+    #[test]
+    fn card_symbol_deserialization() {
+        let symbol: CardSymbol = from_str(
+            r#"{"object": "card_symbol", "symbol": "{G/P}", "svg_uri":
+            "https://svgs.scryfall.io/card-symbols/GP.svg", "loose_variant": null,
+            "english": "one green mana or two life", "transposable": false,
+            "represents_mana": true, "appears_in_mana_costs": true, "mana_value": 1.0,
+            "hybrid": false, "phyrexian": true, "cmc": 1.0, "funny": false,
+            "colors": ["G"], "gatherer_alternates": ["{GP}"]}"#,
+        )
+        .unwrap();
+        assert_eq!(symbol.symbol, "{G/P}");
+        assert_eq!(symbol.cmc, Some(1.0));
+        assert_eq!(symbol.colors, [Color::Green]);
+        assert!(symbol.represents_mana && symbol.phyrexian && !symbol.hybrid);
+
+        let symbol: CardSymbol = from_str(
+            r#"{"object": "card_symbol", "symbol": "{T}", "svg_uri": null,
+            "loose_variant": null, "english": "tap this permanent", "transposable": false,
+            "represents_mana": false, "appears_in_mana_costs": false, "cmc": null,
+            "funny": false, "colors": [], "gatherer_alternates": null}"#,
+        )
+        .unwrap();
+        assert_eq!(symbol.cmc, None);
+        assert!(!symbol.represents_mana && !symbol.phyrexian && !symbol.hybrid);
+    }
+}
@@ -6,17 +6,21 @@
 use std::convert::TryFrom;
 use std::marker::PhantomData;
 
+#[cfg(feature = "async")]
+use futures_util::stream::{self, Stream};
 use httpstatus::StatusCode;
+#[cfg(feature = "async")]
+use reqwest::header::{HeaderMap, HeaderValue};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use ureq::Agent;
 use url::Url;
 
+#[cfg(feature = "async")]
+use crate::client::parse_retry_after;
+use crate::client::ScryfallClient;
 use crate::error::Error;
 use crate::list::{List, ListIter};
 
-thread_local!(static CLIENT: Agent = Agent::new());
-
 /// An unresolved URI returned by the Scryfall API, or generated by this crate.
 ///
 /// The `fetch` method handles requesting the resource from the API endpoint,
@@ -24,13 +28,20 @@ thread_local!(static CLIENT: Agent = Agent::new());
 /// [`List`][crate::list::List]`<_>`, then additional methods `fetch_iter`
 /// and `fetch_all` are available, giving access to objects from all pages
 /// of the collection.
-#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Debug)]
 #[serde(transparent)]
 pub struct Uri<T> {
     url: Url,
     _marker: PhantomData<fn() -> T>,
 }
 
+// Not derived, as that would require `T: Clone`.
+impl<T> Clone for Uri<T> {
+    fn clone(&self) -> Self {
+        Uri::from(self.url.clone())
+    }
+}
+
 impl<T> TryFrom<&str> for Uri<T> {
     type Error = crate::error::Error;
 
@@ -64,7 +75,13 @@ impl<T: DeserializeOwned> Uri<T> {
     /// assert_eq!(bolt.mana_cost, Some("{R}".to_string()));
     /// ```
     pub fn fetch(&self) -> crate::Result<T> {
-        match self.fetch_raw() {
+        self.fetch_with(&ScryfallClient::current())
+    }
+
+    /// Fetches a resource like [`fetch`][Self::fetch], using `client` instead
+    /// of the default client.
+    pub fn fetch_with(&self, client: &ScryfallClient) -> crate::Result<T> {
+        match self.fetch_raw_with(client) {
             Ok(response) => match response.status() {
                 200..=299 => Ok(serde_json::from_reader(response.into_reader())?),
                 status => Err(Error::HttpError(StatusCode::from(status))),
@@ -74,14 +91,116 @@ impl<T: DeserializeOwned> Uri<T> {
     }
 
     pub(crate) fn fetch_raw(&self) -> crate::Result<ureq::Response> {
-        match CLIENT.with(|client| client.request_url("GET", &self.url).call()) {
-            Ok(response) => Ok(response),
-            Err(ureq::Error::Status(400..=599, response)) => Err(Error::ScryfallError(
-                serde_json::from_reader(response.into_reader())?,
-            )),
-            Err(err) => Err(Error::UreqError(err.into(), self.url.to_string())),
+        self.fetch_raw_with(&ScryfallClient::current())
+    }
+
+    fn fetch_raw_with(&self, client: &ScryfallClient) -> crate::Result<ureq::Response> {
+        client.send(&self.url, |agent| {
+            agent.request_url("GET", &self.url).call()
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: DeserializeOwned> Uri<T> {
+    /// Fetches a resource like [`fetch`][Self::fetch], awaiting the response
+    /// instead of blocking the thread.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use std::convert::TryFrom;
+    /// #
+    /// # use scryfall::card::Card;
+    /// # use scryfall::uri::Uri;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let uri =
+    ///     Uri::<Card>::try_from("https://api.scryfall.com/cards/named?exact=Lightning+Bolt").unwrap();
+    /// let bolt = uri.fetch_async().await.unwrap();
+    /// assert_eq!(bolt.mana_cost, Some("{R}".to_string()));
+    /// # }
+    /// ```
+    pub async fn fetch_async(&self) -> crate::Result<T> {
+        self.fetch_async_with(&ScryfallClient::current()).await
+    }
+
+    /// Fetches a resource like [`fetch_async`][Self::fetch_async], using
+    /// `client` instead of the default client.
+    pub async fn fetch_async_with(&self, client: &ScryfallClient) -> crate::Result<T> {
+        let response = self.fetch_raw_async_with(client, HeaderMap::new()).await?;
+        let body = response.bytes().await.map_err(|e| self.reqwest_error(e))?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    pub(crate) async fn fetch_raw_async(&self) -> crate::Result<reqwest::Response> {
+        self.fetch_raw_async_with(&ScryfallClient::current(), HeaderMap::new())
+            .await
+    }
+
+    /// Fetches the resource like `fetch_raw_async`, asking for it to be
+    /// gzip-compressed. The body is left compressed if the server does so.
+    pub(crate) async fn fetch_gzip_async(&self) -> crate::Result<reqwest::Response> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            reqwest::header::ACCEPT_ENCODING,
+            HeaderValue::from_static("gzip"),
+        );
+        self.fetch_raw_async_with(&ScryfallClient::current(), headers)
+            .await
+    }
+
+    /// Sends the request with `headers` like `fetch_raw_with`, waiting for
+    /// the rate limit and between retries without blocking the thread.
+    async fn fetch_raw_async_with(
+        &self,
+        client: &ScryfallClient,
+        headers: HeaderMap,
+    ) -> crate::Result<reqwest::Response> {
+        let mut attempt = 0;
+        let response = loop {
+            tokio::time::sleep(client.rate_limit_delay()).await;
+            let result = client
+                .async_client()
+                .get(self.url.clone())
+                .headers(headers.clone())
+                .send()
+                .await;
+            let retry_after = match &result {
+                Ok(response)
+                    if response.status().as_u16() == 429 || response.status().is_server_error() =>
+                {
+                    Some(
+                        response
+                            .headers()
+                            .get(reqwest::header::RETRY_AFTER)
+                            .and_then(|value| value.to_str().ok())
+                            .and_then(parse_retry_after),
+                    )
+                },
+                Err(e) if e.is_connect() || e.is_timeout() || e.is_request() => Some(None),
+                _ => None,
+            };
+            match retry_after.and_then(|retry_after| client.retry_delay(attempt, retry_after)) {
+                Some(delay) => {
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                },
+                None => break result.map_err(|e| self.reqwest_error(e))?,
+            }
+        };
+        match response.status().as_u16() {
+            200..=299 => Ok(response),
+            400..=599 => {
+                let body = response.bytes().await.map_err(|e| self.reqwest_error(e))?;
+                Err(Error::ScryfallError(serde_json::from_slice(&body)?))
+            },
+            status => Err(Error::HttpError(StatusCode::from(status))),
         }
     }
+
+    pub(crate) fn reqwest_error(&self, error: reqwest::Error) -> Error {
+        Error::ReqwestError(Box::new(error), self.url.to_string())
+    }
 }
 
 impl<T: DeserializeOwned> Uri<List<T>> {
@@ -136,3 +255,67 @@ impl<T: DeserializeOwned> Uri<List<T>> {
         Ok(items)
     }
 }
+
+#[cfg(feature = "async")]
+impl<T: DeserializeOwned> Uri<List<T>> {
+    /// Lazily streams items from all pages of a list like
+    /// [`fetch_iter`][Self::fetch_iter]. Following pages are requested once the
+    /// previous page has been exhausted. If a page fails to load, its error is
+    /// the last item of the stream.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use std::convert::TryFrom;
+    /// #
+    /// # use futures_util::{pin_mut, StreamExt};
+    /// # use scryfall::Card;
+    /// # use scryfall::list::List;
+    /// # use scryfall::uri::Uri;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let uri = Uri::<List<Card>>::try_from("https://api.scryfall.com/cards/search?q=zurgo").unwrap();
+    /// let cards = uri.fetch_iter_async().await.unwrap();
+    /// pin_mut!(cards);
+    /// let mut found = false;
+    /// while let Some(card) = cards.next().await {
+    ///     found |= card.unwrap().name.contains("Bellstriker");
+    /// }
+    /// assert!(found);
+    /// # }
+    /// ```
+    pub async fn fetch_iter_async(&self) -> crate::Result<impl Stream<Item = crate::Result<T>>> {
+        let page = self.fetch_async().await?;
+        let state = Some((page.data.into_iter(), page.next_page));
+        Ok(stream::unfold(state, |state| async move {
+            let (mut items, mut next_page) = state?;
+            loop {
+                if let Some(item) = items.next() {
+                    return Some((Ok(item), Some((items, next_page))));
+                }
+                match next_page?.fetch_async().await {
+                    Ok(page) => {
+                        items = page.data.into_iter();
+                        next_page = page.next_page;
+                    },
+                    Err(e) => return Some((Err(e), None)),
+                }
+            }
+        }))
+    }
+
+    /// Eagerly fetches items from all pages of a list like
+    /// [`fetch_all`][Self::fetch_all]. If any of the pages fail to load,
+    /// returns an error.
+    pub async fn fetch_all_async(&self) -> crate::Result<Vec<T>> {
+        let mut items = vec![];
+        let mut next_page = Some(self.fetch_async().await?);
+        while let Some(page) = next_page {
+            items.extend(page.data.into_iter());
+            next_page = match page.next_page {
+                Some(uri) => Some(uri.fetch_async().await?),
+                None => None,
+            };
+        }
+        Ok(items)
+    }
+}
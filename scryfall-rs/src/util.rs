@@ -1,20 +1,37 @@
 //! Module containing utility functions and structs.
-use once_cell::sync::Lazy;
 use serde::{Deserialize, Deserializer};
 use url::Url;
 
+use crate::client::ScryfallClient;
+
 pub(crate) mod array_stream_reader;
+#[cfg(test)]
+pub(crate) mod mock_server;
+
+/// The [cards](https://scryfall.com/docs/api/cards) endpoint of the default client.
+pub fn cards_url() -> Url {
+    ScryfallClient::current().endpoint("cards/")
+}
+
+/// The [sets](https://scryfall.com/docs/api/sets) endpoint of the default client.
+pub fn sets_url() -> Url {
+    ScryfallClient::current().endpoint("sets/")
+}
 
-/// The [scryfall](https://scryfall.com/docs/api) endpoint.
-pub static ROOT_URL: Lazy<Url> = Lazy::new(|| Url::parse("https://api.scryfall.com/").unwrap());
-/// The [cards](https://scryfall.com/docs/api/cards) endpoint.
-pub static CARDS_URL: Lazy<Url> = Lazy::new(|| ROOT_URL.join("cards/").unwrap());
-/// The [sets](https://scryfall.com/docs/api/sets) endpoint.
-pub static SETS_URL: Lazy<Url> = Lazy::new(|| ROOT_URL.join("sets/").unwrap());
-/// The [bulk-data](https://scryfall.com/docs/api/bulk-data) endpoint.
-pub static BULK_DATA_URL: Lazy<Url> = Lazy::new(|| ROOT_URL.join("bulk-data/").unwrap());
-/// The [catalog](https://scryfall.com/docs/api/catalogs) endpoint.
-pub static CATALOG_URL: Lazy<Url> = Lazy::new(|| ROOT_URL.join("catalog/").unwrap());
+/// The [bulk-data](https://scryfall.com/docs/api/bulk-data) endpoint of the default client.
+pub fn bulk_data_url() -> Url {
+    ScryfallClient::current().endpoint("bulk-data/")
+}
+
+/// The [catalog](https://scryfall.com/docs/api/catalogs) endpoint of the default client.
+pub fn catalog_url() -> Url {
+    ScryfallClient::current().endpoint("catalog/")
+}
+
+/// The [symbology](https://scryfall.com/docs/api/card-symbols) endpoint of the default client.
+pub fn symbology_url() -> Url {
+    ScryfallClient::current().endpoint("symbology/")
+}
 
 /// The [rulings](https://scryfall.com/docs/api/rulings) path segment, which goes on the end of a
 /// card URL.
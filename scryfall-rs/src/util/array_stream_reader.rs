@@ -14,12 +14,24 @@ pub(crate) struct ArrayStreamReader<T> {
 
 impl<T: io::Read> ArrayStreamReader<T> {
     pub(crate) fn new_buffered(inner: T) -> BufReader<Self> {
-        BufReader::new(ArrayStreamReader {
+        BufReader::new(ArrayStreamReader::new(inner))
+    }
+
+    pub(crate) fn new(inner: T) -> Self {
+        ArrayStreamReader {
             inner,
             depth: None,
             inside_string: false,
             escape_next: false,
-        })
+        }
+    }
+
+    /// The inner reader, e.g. to append input that arrived after the reader
+    /// ran out of it. Reading in the middle of the outer array without input
+    /// fails with [`io::ErrorKind::UnexpectedEof`] and can be retried.
+    #[cfg(feature = "async")]
+    pub(crate) fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
     }
 }
 
@@ -56,7 +68,7 @@ impl<T: io::Read> io::Read for ArrayStreamReader<T> {
 
             let mut tmp_pos = 0;
             let mut buf_pos = 0;
-            for (i, b) in tmp.iter().cloned().enumerate() {
+            for (i, b) in tmp[..byte_count].iter().cloned().enumerate() {
                 if self.depth.is_none() {
                     match b {
                         b'[' => {
@@ -162,6 +174,24 @@ mod tests {
         );
     }
 
+    /// Reads a single byte at a time.
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl io::Read for ByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            (&mut self.0).take(1).read(buf)
+        }
+    }
+
+    #[test]
+    fn escape_at_end_of_short_read() {
+        let mut dst = vec![];
+        ArrayStreamReader::new_buffered(ByteReader(br#"["\"", 1]"#))
+            .read_to_end(&mut dst)
+            .unwrap();
+        assert_eq!(dst, br#""\""  1 "#);
+    }
+
     #[test]
     fn string_escapes() {
         assert_eq!(
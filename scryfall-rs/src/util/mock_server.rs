@@ -0,0 +1,60 @@
+//! A minimal HTTP server answering requests with canned responses, for
+//! testing requests without the network.
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+use crate::client::ScryfallClient;
+
+/// Answers one request per response, given as status line, extra headers
+/// and body, and returns the requests, each a head followed by the body.
+pub(crate) fn mock_server(
+    responses: Vec<(&'static str, &'static str, &'static str)>,
+) -> (String, thread::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("http://{}/mirror", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut requests = vec![];
+        for (status, headers, body) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            // Reads up to the empty line ending the head.
+            while reader.read_line(&mut request).unwrap() > 2 {}
+            let content_length = request
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                .map_or(0, |(_, value)| value.trim().parse().unwrap());
+            let mut content = vec![0; content_length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {}\r\n{}Content-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                headers,
+                body.len(),
+                body
+            )
+            .unwrap();
+            requests.push(request);
+        }
+        requests
+    });
+    (address, handle)
+}
+
+/// A client for the server at `address` that retries quickly.
+pub(crate) fn mock_client(address: String, max_retries: u32) -> ScryfallClient {
+    ScryfallClient::builder()
+        .base_url(address)
+        .max_retries(max_retries)
+        .user_agent("mock-test/1.0")
+        .timeout(Duration::from_secs(5))
+        .backoff(Duration::from_millis(1), Duration::from_millis(10))
+        .build()
+        .unwrap()
+}
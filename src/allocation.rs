@@ -0,0 +1,102 @@
+use scryfall::Card;
+
+use crate::{card_utils::owned_copies, commander::synergy_score, deck::Deck};
+
+/// A card more decks want than there are owned copies of.
+#[derive(Debug, Clone)]
+pub struct CopyConflict {
+    pub card: Card,
+    pub owned: usize,
+    /// Commanders of the decks wanting the card with the card's synergy score in their deck,
+    /// best synergy first. The first `owned` of them get a copy.
+    pub contenders: Vec<(Card, usize)>,
+}
+
+impl CopyConflict {
+    pub fn suggested_decks(&self) -> &[(Card, usize)] {
+        &self.contenders[..self.owned.min(self.contenders.len())]
+    }
+
+    pub fn short_decks(&self) -> &[(Card, usize)] {
+        &self.contenders[self.owned.min(self.contenders.len())..]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Allocation {
+    /// The decks with contested cards removed from the decks that did not get a copy
+    pub decks: Vec<Deck>,
+    pub conflicts: Vec<CopyConflict>,
+}
+
+/// Shares the owned copies of a collection between several decks. Cards wanted by more decks
+/// than there are copies go to the decks where they have the highest synergy, a commander
+/// always stays in its own deck.
+pub fn allocate_copies(mut decks: Vec<Deck>, collection: &[(Card, usize)]) -> Allocation {
+    let mut wanted_cards: Vec<(&Card, Vec<usize>)> = vec![];
+    for (deck_index, deck) in decks.iter().enumerate() {
+        for card in deck_cards(deck) {
+            match wanted_cards
+                .iter_mut()
+                .find(|(wanted_card, _)| wanted_card.oracle_id == card.oracle_id)
+            {
+                Some((_, deck_indices)) => deck_indices.push(deck_index),
+                None => wanted_cards.push((card, vec![deck_index])),
+            }
+        }
+    }
+
+    let mut conflicts = vec![];
+    let mut removals = vec![];
+    for (card, mut deck_indices) in wanted_cards {
+        let owned = owned_copies(card, collection);
+        if deck_indices.len() <= owned {
+            continue;
+        }
+        let priority = |deck_index: usize| {
+            let deck = &decks[deck_index];
+            if deck.commander.oracle_id == card.oracle_id {
+                usize::MAX
+            } else {
+                synergy_score(card, &deck.keywords)
+            }
+        };
+        // Stable sort keeps the order of the decks for equal synergy
+        deck_indices.sort_by_key(|&deck_index| std::cmp::Reverse(priority(deck_index)));
+        removals.extend(
+            deck_indices
+                .iter()
+                .skip(owned)
+                .map(|&deck_index| (deck_index, card.oracle_id)),
+        );
+        conflicts.push(CopyConflict {
+            card: card.to_owned(),
+            owned,
+            contenders: deck_indices
+                .into_iter()
+                .map(|deck_index| {
+                    (
+                        decks[deck_index].commander.to_owned(),
+                        synergy_score(card, &decks[deck_index].keywords),
+                    )
+                })
+                .collect(),
+        });
+    }
+
+    for (deck_index, oracle_id) in removals {
+        let deck = &mut decks[deck_index];
+        deck.spells.retain(|card| card.oracle_id != oracle_id);
+        deck.lands
+            .nonbasic_lands
+            .retain(|card| card.oracle_id != oracle_id);
+    }
+
+    Allocation { decks, conflicts }
+}
+
+fn deck_cards(deck: &Deck) -> impl Iterator<Item = &Card> {
+    std::iter::once(&deck.commander)
+        .chain(deck.spells.iter())
+        .chain(deck.lands.nonbasic_lands.iter())
+}
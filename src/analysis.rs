@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use scryfall::Card;
+
+use crate::{
+    card_utils::is_land,
+    commander::{extract_catalogued_keywords, keyword_catalogs, synergy_score},
+    exclusion::Exclusions,
+    match_colors_and_keywords,
+};
+
+#[derive(Debug, Clone)]
+pub struct DeckAnalysis {
+    pub commander: Card,
+    pub keywords: Vec<String>,
+    /// Collection cards playing into the commander's keywords which are not in the deck
+    pub missing_keyword_cards: HashMap<String, Vec<Card>>,
+    /// Nonland cards of the deck that don't play into any of the commander's keywords
+    pub off_theme_cards: Vec<Card>,
+    /// Suggested replacements for off theme cards, best synergy first
+    pub swaps: Vec<Swap>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Swap {
+    pub remove: Card,
+    pub add: Card,
+    pub synergy: usize,
+}
+
+/// Compares an existing deck with the commander's keywords and the cards of the collection.
+/// Excluded cards are not suggested.
+pub async fn analyze_deck(
+    commander: &Card,
+    deck: &[Card],
+    collection: &[Card],
+    exclusions: &Exclusions,
+) -> DeckAnalysis {
+    let keywords =
+        extract_catalogued_keywords(vec![commander.to_owned()], &keyword_catalogs().await)
+            .pop()
+            .map(|(_, keywords)| keywords)
+            .unwrap_or_default();
+    analyze_deck_with_keywords(commander, &keywords, deck, collection, exclusions)
+}
+
+pub fn analyze_deck_with_keywords(
+    commander: &Card,
+    keywords: &[String],
+    deck: &[Card],
+    collection: &[Card],
+    exclusions: &Exclusions,
+) -> DeckAnalysis {
+    let in_deck = |card: &Card| {
+        card.oracle_id == commander.oracle_id
+            || deck
+                .iter()
+                .any(|deck_card| deck_card.oracle_id == card.oracle_id)
+    };
+
+    let mut missing_keyword_cards =
+        match_colors_and_keywords(commander, keywords, collection, exclusions);
+    missing_keyword_cards
+        .values_mut()
+        .for_each(|cards| cards.retain(|card| !in_deck(card)));
+    missing_keyword_cards.retain(|_, cards| !cards.is_empty());
+
+    let mut off_theme_cards: Vec<Card> = deck
+        .iter()
+        .filter(|card| !is_land(card) && card.oracle_id != commander.oracle_id)
+        .filter(|card| synergy_score(card, keywords) == 0)
+        .cloned()
+        .collect();
+    // Least popular cards are suggested to be swapped out first
+    off_theme_cards.sort_by_key(|card| std::cmp::Reverse(card.edhrec_rank.unwrap_or(usize::MAX)));
+
+    let mut additions: Vec<(usize, &Card)> = missing_keyword_cards
+        .values()
+        .flatten()
+        .filter(|card| !is_land(card))
+        .map(|card| (synergy_score(card, keywords), card))
+        .collect();
+    additions.sort_by(|(score1, card1), (score2, card2)| {
+        score2.cmp(score1).then_with(|| card1.name.cmp(&card2.name))
+    });
+    let swaps = off_theme_cards
+        .iter()
+        .zip(additions)
+        .map(|(remove, (synergy, add))| Swap {
+            remove: remove.to_owned(),
+            add: add.to_owned(),
+            synergy,
+        })
+        .collect();
+
+    DeckAnalysis {
+        commander: commander.to_owned(),
+        keywords: keywords.to_vec(),
+        missing_keyword_cards,
+        off_theme_cards,
+        swaps,
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+use futures::stream::{self, StreamExt};
+use scryfall::{card::Price, Card};
+use serde::Deserialize;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::storage::Printing;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Currency {
+    #[default]
+    Usd,
+    Eur,
+    Tix,
+}
+
+impl Currency {
+    pub fn price(&self, price: &Price) -> Option<f32> {
+        let price = match self {
+            Currency::Usd => &price.usd,
+            Currency::Eur => &price.eur,
+            Currency::Tix => &price.tix,
+        };
+        price.as_ref().and_then(|price| price.parse().ok())
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Currency::Usd => "usd",
+            Currency::Eur => "eur",
+            Currency::Tix => "tix",
+        })
+    }
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    fn from_str(currency: &str) -> Result<Self, Self::Err> {
+        match currency.to_lowercase().as_str() {
+            "usd" => Ok(Currency::Usd),
+            "eur" => Ok(Currency::Eur),
+            "tix" => Ok(Currency::Tix),
+            _ => Err(format!(
+                "Unknown currency {currency}, expected usd, eur or tix"
+            )),
+        }
+    }
+}
+
+/// Printing lookups running at the same time, further ones wait for one of them to finish.
+const CONCURRENT_LOOKUPS: usize = 8;
+
+/// Price limits for building decks and buying cards.
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    pub currency: Currency,
+    /// Limit for the sum of all card prices
+    pub total_limit: Option<f32>,
+    /// Limit for the price of a single card
+    pub card_limit: Option<f32>,
+    /// Look up the cheapest printing of every card on Scryfall instead of using the stored one
+    pub cheapest_printings: bool,
+    /// Printings of the collection by oracle id, used instead of cheaper ones
+    pub owned_printings: HashMap<Uuid, Printing>,
+    /// Preferred languages of looked up printings, most preferred first, e.g. `de`
+    pub languages: Vec<String>,
+    /// Printings by oracle id, looked up by [`Budget::fetch_printings`]
+    pub printings: HashMap<Uuid, Vec<Card>>,
+}
+
+impl Budget {
+    pub fn is_limited(&self) -> bool {
+        self.total_limit.is_some() || self.card_limit.is_some()
+    }
+
+    pub fn price(&self, card: &Card) -> Option<f32> {
+        self.currency.price(&card.prices)
+    }
+
+    /// Total price of the cards, cards without a price in the currency count as free.
+    pub fn total<'a>(&self, cards: impl IntoIterator<Item = &'a Card>) -> f32 {
+        cards.into_iter().filter_map(|card| self.price(card)).sum()
+    }
+
+    /// Whether a card costing `price` fits the limits after `spent` has been spent. Without
+    /// limits every card fits, with limits cards without a known price never do.
+    pub fn allows(&self, price: Option<f32>, spent: f32) -> bool {
+        if !self.is_limited() {
+            return true;
+        }
+        price.map_or(false, |price| {
+            self.card_limit.map_or(true, |limit| price <= limit)
+                && self
+                    .total_limit
+                    .map_or(true, |limit| spent + price <= limit)
+        })
+    }
+
+    /// Takes cards in order as long as they fit the remaining budget, using the cheapest printing
+    /// if enabled.
+    pub fn select<'a>(
+        &self,
+        cards: impl IntoIterator<Item = &'a Card>,
+        spent: &mut f32,
+        count: usize,
+    ) -> Vec<Card> {
+        let mut selected = vec![];
+        for card in cards {
+            if selected.len() == count {
+                break;
+            }
+            let card = self.printing(card);
+            let price = self.price(&card);
+            if self.allows(price, *spent) {
+                *spent += price.unwrap_or_default();
+                selected.push(card);
+            }
+        }
+        selected
+    }
+
+    /// Looks up the printings of the cards on Scryfall, `CONCURRENT_LOOKUPS` at a time, if the
+    /// cheapest printings or languages are used, skipping cards whose printings are already known.
+    /// Printings in other languages than English are only looked up with preferred languages.
+    pub async fn fetch_printings<'a>(&mut self, cards: impl IntoIterator<Item = &'a Card>) {
+        if !self.cheapest_printings && self.languages.is_empty() {
+            return;
+        }
+        let mut seen = HashSet::new();
+        let cards: Vec<&Card> = cards
+            .into_iter()
+            .filter(|card| !self.printings.contains_key(&card.oracle_id))
+            .filter(|card| seen.insert(card.oracle_id))
+            .collect();
+        let all_languages = !self.languages.is_empty();
+        let printings: Vec<_> = stream::iter(&cards)
+            .map(|card| async move {
+                if !all_languages {
+                    card.prints_search_uri.fetch_all_async().await
+                } else {
+                    Card::search_all_async(format!(
+                        "oracleid:{} unique:prints lang:any",
+                        card.oracle_id
+                    ))
+                    .await
+                }
+            })
+            .buffered(CONCURRENT_LOOKUPS)
+            .collect()
+            .await;
+        for (card, printings) in cards.into_iter().zip(printings) {
+            match printings {
+                Ok(printings) => {
+                    self.printings.insert(card.oracle_id, printings);
+                }
+                Err(error) => warn!("Printings of {} not found: {error}", card.name),
+            }
+        }
+    }
+
+    /// The printing of the card to use. Of the printings looked up by
+    /// [`fetch_printings`][Budget::fetch_printings] the owned one is preferred, otherwise one of
+    /// the most preferred language available: the cheapest one in the budget's currency if the
+    /// cheapest printings are used, else the first one. Without looked up printings it's the
+    /// stored one.
+    pub fn printing(&self, card: &Card) -> Card {
+        let printings = match self.printings.get(&card.oracle_id) {
+            Some(printings) => printings,
+            None => return card.to_owned(),
+        };
+        let owned_printing = self.owned_printings.get(&card.oracle_id).and_then(|owned| {
+            printings.iter().find(|printing| {
+                printing.set == owned.set && printing.collector_number == owned.collector_number
+            })
+        });
+        let language_rank = |printing: &Card| {
+            self.languages
+                .iter()
+                .position(|language| *language == printing.lang)
+                .unwrap_or(self.languages.len())
+        };
+        let best_rank = printings.iter().map(language_rank).min();
+        let preferred: Vec<&Card> = printings
+            .iter()
+            .filter(|printing| Some(language_rank(printing)) == best_rank)
+            .collect();
+        let cheapest = || {
+            preferred
+                .iter()
+                .filter_map(|&printing| self.price(printing).map(|price| (printing, price)))
+                .min_by(|(_, price1), (_, price2)| price1.total_cmp(price2))
+                .map(|(printing, _)| printing)
+        };
+        owned_printing
+            .or_else(|| self.cheapest_printings.then(cheapest).flatten())
+            .or_else(|| {
+                preferred
+                    .first()
+                    .filter(|_| !self.languages.is_empty())
+                    .copied()
+            })
+            .unwrap_or(card)
+            .to_owned()
+    }
+}
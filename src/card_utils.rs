@@ -1,5 +1,5 @@
 use scryfall::{
-    card::{CardFace, Color},
+    card::{CardFace, Color, ManaCost},
     Card,
 };
 use std::fmt::Write;
@@ -41,3 +41,60 @@ pub fn extract_card_colors(card: &Card) -> Vec<Color> {
 fn extract_multiple_faces(card: &Card) -> &Vec<CardFace> {
     card.card_faces.as_ref().expect("Card with multiple faces")
 }
+
+/// Mana cost of the card, the costs of the faces separated by ` // ` like those of split cards.
+/// Faces without a cost, like the back of transforming cards, are left out.
+pub fn extract_mana_cost(card: &Card) -> String {
+    match &card.mana_cost {
+        Some(mana_cost) => mana_cost.to_owned(),
+        None => card
+            .card_faces
+            .iter()
+            .flatten()
+            .map(|face| face.mana_cost.as_str())
+            .filter(|mana_cost| !mana_cost.is_empty())
+            .collect::<Vec<_>>()
+            .join(" // "),
+    }
+}
+
+/// Parsed mana costs of the card, one per half of split cards or face of double-faced cards.
+/// Costs with symbols that can't be parsed, like the half mana of Un-cards, are left out.
+pub fn extract_mana_costs(card: &Card) -> Vec<ManaCost> {
+    extract_mana_cost(card)
+        .split(" // ")
+        .filter_map(|cost| cost.parse().ok())
+        .collect()
+}
+
+pub fn card_matches_keyword(card: &Card, keyword: &str) -> bool {
+    card.type_line.contains(keyword) || extract_oracle_text(card).contains(keyword)
+}
+
+pub fn is_land(card: &Card) -> bool {
+    card.type_line.contains("Land")
+}
+
+/// Stored image of the card or of its front face in the given size, e.g. `normal` or `small`.
+pub fn extract_image_uri(card: &Card, size: &str) -> Option<String> {
+    match card.image_uris.get(size) {
+        Some(image_uri) => Some(image_uri.to_string()),
+        None => card
+            .card_faces
+            .as_ref()?
+            .first()?
+            .image_uris
+            .as_ref()?
+            .get(size)
+            .cloned(),
+    }
+}
+
+/// Number of owned copies of the card over all printings in the collection.
+pub fn owned_copies(card: &Card, collection: &[(Card, usize)]) -> usize {
+    collection
+        .iter()
+        .filter(|(owned_card, _)| owned_card.oracle_id == card.oracle_id)
+        .map(|(_, quantity)| quantity)
+        .sum()
+}
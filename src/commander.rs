@@ -1,36 +1,44 @@
 use scryfall::{Card, Catalog};
+use tracing::info;
 
-use crate::card_utils::extract_oracle_text;
+use crate::card_utils::{card_matches_keyword, extract_oracle_text};
 
-pub fn extract_catalogued_keywords(commanders: Vec<Card>) -> Vec<(Card, Vec<String>)> {
-    let catalogs = vec![
-        Catalog::creature_types()
-            .expect("Catalog creature types")
-            .data,
-        Catalog::planeswalker_types()
-            .expect("Catalog planeswalker types")
-            .data,
-        Catalog::land_types().expect("Catalog land types").data,
-        Catalog::artifact_types()
-            .expect("Catalog artifact types")
-            .data,
-        Catalog::enchantment_types()
-            .expect("Catalog enchantment types")
-            .data,
-        Catalog::spell_types().expect("Catalog spell types").data,
-        Catalog::keyword_actions()
-            .expect("Catalog keyword actions")
-            .data,
-        Catalog::ability_words()
-            .expect("Catalog ability words")
-            .data,
-        Catalog::keyword_abilities()
-            .expect("Catalog keyword abilities")
-            .data,
-    ];
+/// Fetches the catalogs of creature, planeswalker, land, artifact, enchantment and spell types,
+/// keyword actions, ability words and keyword abilities concurrently.
+pub async fn keyword_catalogs() -> Vec<Vec<String>> {
+    info!("Fetching keyword catalogs");
+    let catalogs = tokio::try_join!(
+        Catalog::creature_types_async(),
+        Catalog::planeswalker_types_async(),
+        Catalog::land_types_async(),
+        Catalog::artifact_types_async(),
+        Catalog::enchantment_types_async(),
+        Catalog::spell_types_async(),
+        Catalog::keyword_actions_async(),
+        Catalog::ability_words_async(),
+        Catalog::keyword_abilities_async(),
+    )
+    .expect("Keyword catalogs");
+    vec![
+        catalogs.0.data,
+        catalogs.1.data,
+        catalogs.2.data,
+        catalogs.3.data,
+        catalogs.4.data,
+        catalogs.5.data,
+        catalogs.6.data,
+        catalogs.7.data,
+        catalogs.8.data,
+    ]
+}
+
+pub fn extract_catalogued_keywords(
+    commanders: Vec<Card>,
+    catalogs: &[Vec<String>],
+) -> Vec<(Card, Vec<String>)> {
     commanders
         .iter()
-        .map(|commander| (commander.to_owned(), find_keywords(commander, &catalogs)))
+        .map(|commander| (commander.to_owned(), find_keywords(commander, catalogs)))
         .collect()
 }
 
@@ -50,3 +58,11 @@ fn find_keywords(commander: &Card, catalogs: &[Vec<String>]) -> Vec<String> {
 
     found_keywords
 }
+
+/// Number of the commander's keywords a card plays into.
+pub fn synergy_score(card: &Card, keywords: &[String]) -> usize {
+    keywords
+        .iter()
+        .filter(|keyword| card_matches_keyword(card, keyword))
+        .count()
+}
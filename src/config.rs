@@ -0,0 +1,174 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    budget::Currency,
+    exclusion::{parse_local_query, Exclusions, PowerLevel},
+    theme::{read_theme_rules, ThemeRule},
+};
+
+pub const CONFIG_FILE_NAME: &str = "mtg-commander-suggestions.toml";
+
+/// Contents of a config file: default settings and named profiles, e.g. per playgroup, whose
+/// settings take precedence over the defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub settings: Settings,
+    pub profiles: HashMap<String, Settings>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// MongoDB connection string of the store
+    pub database_uri: Option<String>,
+    /// Base URL of the Scryfall API, e.g. a mirror
+    pub scryfall_url: Option<String>,
+    /// Output format of the commands, e.g. `json`
+    pub output: Option<String>,
+    /// Format of the export command, e.g. `archidekt`
+    pub export_format: Option<String>,
+    /// Names of cards the players don't want to play
+    pub exclude: Vec<String>,
+    /// Names of cards banned by the playgroup
+    pub house_bans: Vec<String>,
+    /// Oracle ids of excluded cards, matching all printings and languages
+    pub exclude_oracle_ids: Vec<Uuid>,
+    /// Search terms of excluded cards, e.g. `o:"extra turn"`
+    pub exclude_queries: Vec<String>,
+    pub power_level: Option<PowerLevel>,
+    pub budget: Option<f32>,
+    pub card_budget: Option<f32>,
+    pub currency: Option<Currency>,
+    pub cheapest_printings: Option<bool>,
+    pub land_count: Option<usize>,
+    pub buy_limit: Option<usize>,
+    /// Files of theme rules grouping cards next to the keywords, relative to the config file
+    pub theme_files: Vec<PathBuf>,
+    /// Preferred languages of printings, most preferred first, e.g. `["de", "en"]`
+    pub languages: Vec<String>,
+}
+
+impl Settings {
+    /// Settings of `self` overridden by those of `other`, lists of cards are combined.
+    pub fn merge(mut self, other: Settings) -> Settings {
+        self.exclude.extend(other.exclude);
+        self.house_bans.extend(other.house_bans);
+        self.exclude_oracle_ids.extend(other.exclude_oracle_ids);
+        self.exclude_queries.extend(other.exclude_queries);
+        self.theme_files.extend(other.theme_files);
+        Settings {
+            database_uri: other.database_uri.or(self.database_uri),
+            scryfall_url: other.scryfall_url.or(self.scryfall_url),
+            output: other.output.or(self.output),
+            export_format: other.export_format.or(self.export_format),
+            exclude: self.exclude,
+            house_bans: self.house_bans,
+            exclude_oracle_ids: self.exclude_oracle_ids,
+            exclude_queries: self.exclude_queries,
+            power_level: other.power_level.or(self.power_level),
+            budget: other.budget.or(self.budget),
+            card_budget: other.card_budget.or(self.card_budget),
+            currency: other.currency.or(self.currency),
+            cheapest_printings: other.cheapest_printings.or(self.cheapest_printings),
+            land_count: other.land_count.or(self.land_count),
+            buy_limit: other.buy_limit.or(self.buy_limit),
+            theme_files: self.theme_files,
+            languages: if other.languages.is_empty() {
+                self.languages
+            } else {
+                other.languages
+            },
+        }
+    }
+
+    /// All cards not to suggest, fails on invalid search terms and on those which can't be
+    /// evaluated locally.
+    pub fn exclusions(&self) -> Result<Exclusions, String> {
+        Ok(Exclusions {
+            names: self
+                .exclude
+                .iter()
+                .chain(&self.house_bans)
+                .cloned()
+                .collect(),
+            oracle_ids: self.exclude_oracle_ids.to_owned(),
+            queries: self
+                .exclude_queries
+                .iter()
+                .map(|query| {
+                    parse_local_query(query)
+                        .map_err(|error| format!("Invalid exclusion {query}: {error}"))
+                })
+                .collect::<Result<_, _>>()?,
+            power_level: self.power_level.unwrap_or_default(),
+        })
+    }
+
+    /// The rules of all theme files, fails on unreadable files and invalid search terms.
+    pub fn theme_rules(&self) -> Result<Vec<ThemeRule>, String> {
+        let mut rules = Vec::new();
+        for path in &self.theme_files {
+            rules.extend(read_theme_rules(path)?);
+        }
+        Ok(rules)
+    }
+}
+
+impl Config {
+    /// The default settings merged with those of the profile.
+    pub fn settings(&self, profile: Option<&str>) -> Result<Settings, String> {
+        match profile {
+            None => Ok(self.settings.to_owned()),
+            Some(profile) => self
+                .profiles
+                .get(profile)
+                .map(|profile_settings| self.settings.to_owned().merge(profile_settings.to_owned()))
+                .ok_or_else(|| format!("Unknown profile {profile}")),
+        }
+    }
+}
+
+/// The config file in the working directory, or else in the user's config directory, e.g.
+/// `~/.config` on Linux.
+pub fn find_config_file() -> Option<PathBuf> {
+    std::iter::once(PathBuf::from(CONFIG_FILE_NAME))
+        .chain(dirs::config_dir().map(|config_dir| config_dir.join(CONFIG_FILE_NAME)))
+        .find(|path| path.is_file())
+}
+
+pub fn read_config(path: &Path) -> Result<Config, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| format!("Reading {}: {error}", path.display()))?;
+    let mut config =
+        parse_config(&text).map_err(|error| format!("Parsing {}: {error}", path.display()))?;
+    if let Some(directory) = path.parent() {
+        for settings in std::iter::once(&mut config.settings).chain(config.profiles.values_mut()) {
+            settings.theme_files = settings
+                .theme_files
+                .iter()
+                .map(|theme_file| directory.join(theme_file))
+                .collect();
+        }
+    }
+    Ok(config)
+}
+
+/// Parses the settings at the top level and the tables below `[profiles.<name>]`, unknown
+/// settings are rejected.
+pub fn parse_config(text: &str) -> Result<Config, toml::de::Error> {
+    let mut table: toml::Table = toml::from_str(text)?;
+    let profiles = match table.remove("profiles") {
+        Some(profiles) => profiles.try_into()?,
+        None => HashMap::new(),
+    };
+    Ok(Config {
+        settings: toml::Value::Table(table).try_into()?,
+        profiles,
+    })
+}
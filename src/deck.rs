@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use scryfall::Card;
+
+use crate::{
+    budget::Budget,
+    card_utils::is_land,
+    check_selected,
+    commander::synergy_score,
+    exclusion::Exclusions,
+    is_selected,
+    mana_base::{generate_mana_base, ManaBase},
+    suggest_commanders,
+    theme::{add_themes, ThemeRule},
+};
+
+/// Cards in a commander deck, including the commander.
+pub const DECK_SIZE: usize = 100;
+pub const DEFAULT_LAND_COUNT: usize = 37;
+
+#[derive(Debug, Clone)]
+pub struct Deck {
+    pub commander: Card,
+    pub keywords: Vec<String>,
+    pub spells: Vec<Card>,
+    pub lands: ManaBase,
+}
+
+impl Deck {
+    pub fn cards(&self) -> impl Iterator<Item = &Card> {
+        std::iter::once(&self.commander)
+            .chain(self.spells.iter())
+            .chain(self.lands.nonbasic_lands.iter())
+    }
+
+    /// Value of the deck without its basic lands.
+    pub fn total_value(&self, budget: &Budget) -> f32 {
+        budget.total(self.cards())
+    }
+
+    /// Cards missing to [`DECK_SIZE`], e.g. after sharing the collection with other decks or when
+    /// not enough cards fit the budget.
+    pub fn missing_cards(&self) -> usize {
+        DECK_SIZE.saturating_sub(1 + self.spells.len() + self.lands.land_count())
+    }
+}
+
+/// Builds decks for the suggested commanders, only the selected ones if any are given. Selected
+/// commanders without a suggestion are an error. Cards of the theme rules are candidates next to
+/// those of the keywords. The printings of the cards a deck can be built from are looked up
+/// before building it.
+pub async fn build_decks(
+    collection: &[(Card, usize)],
+    commanders: &[String],
+    land_count: usize,
+    budget: &mut Budget,
+    exclusions: &Exclusions,
+    themes: &[ThemeRule],
+) -> Result<Vec<Deck>, String> {
+    let cards: Vec<Card> = collection.iter().map(|(card, _)| card.to_owned()).collect();
+    let lands: Vec<&Card> = cards.iter().filter(|card| is_land(card)).collect();
+    let mut suggestions = suggest_commanders(&cards, exclusions).await;
+    add_themes(&mut suggestions, themes, &cards, exclusions);
+    check_selected(&suggestions, commanders)?;
+    let mut decks = vec![];
+    for (commander, compatible_cards) in suggestions
+        .iter()
+        .filter(|(commander, _)| is_selected(commander, commanders))
+    {
+        budget
+            .fetch_printings(
+                compatible_cards
+                    .values()
+                    .flatten()
+                    .chain(lands.iter().copied()),
+            )
+            .await;
+        decks.push(build_deck(
+            commander,
+            compatible_cards,
+            &cards,
+            land_count,
+            budget,
+        ));
+    }
+    Ok(decks)
+}
+
+/// Fills a deck with the nonland cards of the commander's keyword groups, best synergy first,
+/// and completes it with a mana base of `land_count` lands from the collection. Cards not
+/// fitting the budget are left out.
+pub fn build_deck(
+    commander: &Card,
+    keyword_cards: &HashMap<String, Vec<Card>>,
+    collection: &[Card],
+    land_count: usize,
+    budget: &Budget,
+) -> Deck {
+    let keywords: Vec<String> = keyword_cards.keys().cloned().collect();
+    let mut candidates: Vec<(usize, &Card)> = keyword_cards
+        .values()
+        .flatten()
+        .filter(|card| !is_land(card) && card.oracle_id != commander.oracle_id)
+        .map(|card| (synergy_score(card, &keywords), card))
+        .collect();
+    candidates.sort_by(|(score1, card1), (score2, card2)| {
+        score2
+            .cmp(score1)
+            .then_with(|| {
+                card1
+                    .edhrec_rank
+                    .unwrap_or(usize::MAX)
+                    .cmp(&card2.edhrec_rank.unwrap_or(usize::MAX))
+            })
+            .then_with(|| card1.name.cmp(&card2.name))
+    });
+    candidates.dedup_by(|(_, card1), (_, card2)| card1.oracle_id == card2.oracle_id);
+
+    let mut spent = budget.price(commander).unwrap_or_default();
+    let spells = budget.select(
+        candidates.into_iter().map(|(_, card)| card),
+        &mut spent,
+        DECK_SIZE.saturating_sub(land_count + 1),
+    );
+    let lands = generate_mana_base(commander, &spells, collection, land_count, budget, spent);
+
+    Deck {
+        commander: commander.to_owned(),
+        keywords,
+        spells,
+        lands,
+    }
+}
@@ -0,0 +1,86 @@
+use std::{fmt, str::FromStr};
+
+use scryfall::{search::query::Query, Card};
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Cards a playgroup doesn't want to see in suggestions, neither as commander nor in a deck.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Exclusions {
+    pub names: Vec<String>,
+    pub oracle_ids: Vec<Uuid>,
+    /// Scryfall searches like `o:"extra turn"` or `t:land -c:g`, evaluated locally. Searches that
+    /// can't be, see [`Query::check_local`], exclude no card.
+    pub queries: Vec<Query>,
+    pub power_level: PowerLevel,
+}
+
+impl Exclusions {
+    /// The collection without the excluded cards, which keeps them out of the mana bases as well.
+    pub fn playable_cards(&self, collection: Vec<(Card, usize)>) -> Vec<(Card, usize)> {
+        collection
+            .into_iter()
+            .filter(|(card, _)| !self.excludes(card))
+            .collect()
+    }
+
+    pub fn excludes(&self, card: &Card) -> bool {
+        self.names.iter().any(|name| name == &card.name)
+            || self.oracle_ids.contains(&card.oracle_id)
+            || self
+                .queries
+                .iter()
+                .any(|query| query.matches(card).unwrap_or(false))
+            || self.power_level.excludes(card)
+    }
+}
+
+/// Parses a Scryfall search to evaluate on the stored cards, fails if it can't be evaluated
+/// locally, e.g. for regular expressions or other printings.
+pub fn parse_local_query(query: &str) -> Result<Query, String> {
+    let query = query.parse::<Query>().map_err(|error| error.to_string())?;
+    query.check_local().map_err(|error| error.to_string())?;
+    Ok(query)
+}
+
+/// Preset limiting the power of suggested cards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerLevel {
+    /// No Game Changers, the cards the Commander format considers to warp games
+    Casual,
+    #[default]
+    Unrestricted,
+}
+
+impl PowerLevel {
+    pub fn excludes(&self, card: &Card) -> bool {
+        match self {
+            PowerLevel::Casual => card.game_changer,
+            PowerLevel::Unrestricted => false,
+        }
+    }
+}
+
+impl fmt::Display for PowerLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            PowerLevel::Casual => "casual",
+            PowerLevel::Unrestricted => "unrestricted",
+        })
+    }
+}
+
+impl FromStr for PowerLevel {
+    type Err = String;
+
+    fn from_str(power_level: &str) -> Result<Self, Self::Err> {
+        match power_level.to_lowercase().as_str() {
+            "casual" => Ok(PowerLevel::Casual),
+            "unrestricted" => Ok(PowerLevel::Unrestricted),
+            _ => Err(format!(
+                "Unknown power level {power_level}, expected casual or unrestricted"
+            )),
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use scryfall::Card;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{deck::Deck, mana_base::basic_land_name, storage::Printing};
+
+/// Import format of deck building sites.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// MTG Arena import text, also read by Moxfield
+    #[default]
+    Arena,
+    /// Archidekt import text
+    Archidekt,
+    /// CSV for Moxfield and Archidekt
+    Csv,
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ExportFormat::Arena => "arena",
+            ExportFormat::Archidekt => "archidekt",
+            ExportFormat::Csv => "csv",
+        })
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(export_format: &str) -> Result<Self, Self::Err> {
+        match export_format.to_lowercase().as_str() {
+            "arena" => Ok(ExportFormat::Arena),
+            "archidekt" => Ok(ExportFormat::Archidekt),
+            "csv" => Ok(ExportFormat::Csv),
+            _ => Err(format!(
+                "Unknown export format {export_format}, expected arena, archidekt or csv"
+            )),
+        }
+    }
+}
+
+/// A line of an exported decklist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportCard {
+    #[serde(rename = "Count")]
+    pub count: usize,
+    #[serde(rename = "Name")]
+    pub name: String,
+    /// Set code of the printing, basic lands have none
+    #[serde(rename = "Edition")]
+    pub set: Option<String>,
+    #[serde(rename = "Collector Number")]
+    pub collector_number: Option<String>,
+    #[serde(rename = "Category")]
+    pub category: ExportCategory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ExportCategory {
+    Commander,
+    Mainboard,
+}
+
+impl ExportCard {
+    /// A line for one copy of the card, naming the owned printing if there is one.
+    fn new(
+        card: &Card,
+        category: ExportCategory,
+        owned_printings: &HashMap<Uuid, Printing>,
+    ) -> Self {
+        let (set, collector_number) = match owned_printings.get(&card.oracle_id) {
+            Some(printing) => (printing.set, printing.collector_number.to_owned()),
+            None => (card.set, card.collector_number.to_owned()),
+        };
+        ExportCard {
+            count: 1,
+            name: card.name.to_owned(),
+            set: Some(set.to_string()),
+            collector_number: Some(collector_number),
+            category,
+        }
+    }
+
+    fn printing(&self) -> String {
+        match (&self.set, &self.collector_number) {
+            (Some(set), Some(collector_number)) => {
+                format!(" ({}) {collector_number}", set.to_uppercase())
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+/// Lines for a built deck: the commander, spells, nonbasic lands and basic lands. Cards are
+/// exported with their owned printing if there is one.
+pub fn deck_export_cards(
+    deck: &Deck,
+    owned_printings: &HashMap<Uuid, Printing>,
+) -> Vec<ExportCard> {
+    let mut export_cards = vec![ExportCard::new(
+        &deck.commander,
+        ExportCategory::Commander,
+        owned_printings,
+    )];
+    export_cards.extend(
+        deck.spells
+            .iter()
+            .chain(&deck.lands.nonbasic_lands)
+            .map(|card| ExportCard::new(card, ExportCategory::Mainboard, owned_printings)),
+    );
+    export_cards.extend(
+        deck.lands
+            .basic_lands
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(color, count)| ExportCard {
+                count: *count,
+                name: basic_land_name(*color).to_owned(),
+                set: None,
+                collector_number: None,
+                category: ExportCategory::Mainboard,
+            }),
+    );
+    export_cards
+}
+
+/// Lines for a commander suggestion: the commander and every matched card once, sorted by name.
+/// Cards are exported with their owned printing if there is one.
+pub fn suggestion_export_cards(
+    commander: &Card,
+    compatible_cards: &HashMap<String, Vec<Card>>,
+    owned_printings: &HashMap<Uuid, Printing>,
+) -> Vec<ExportCard> {
+    let mut cards: Vec<&Card> = compatible_cards
+        .values()
+        .flatten()
+        .filter(|card| card.oracle_id != commander.oracle_id)
+        .collect();
+    cards.sort_by(|card1, card2| card1.name.cmp(&card2.name));
+    cards.dedup_by_key(|card| card.oracle_id);
+    std::iter::once(ExportCard::new(
+        commander,
+        ExportCategory::Commander,
+        owned_printings,
+    ))
+    .chain(
+        cards
+            .into_iter()
+            .map(|card| ExportCard::new(card, ExportCategory::Mainboard, owned_printings)),
+    )
+    .collect()
+}
+
+/// MTG Arena import text with a `Commander` and a `Deck` section, e.g. `1 Sol Ring (C21) 263`.
+/// Moxfield imports this format as well.
+pub fn arena_text(export_cards: &[ExportCard]) -> String {
+    let section = |category: ExportCategory| -> String {
+        export_cards
+            .iter()
+            .filter(|export_card| export_card.category == category)
+            .map(|export_card| {
+                format!(
+                    "{} {}{}\n",
+                    export_card.count,
+                    export_card.name,
+                    export_card.printing()
+                )
+            })
+            .collect()
+    };
+    format!(
+        "Commander\n{}\nDeck\n{}",
+        section(ExportCategory::Commander),
+        section(ExportCategory::Mainboard)
+    )
+}
+
+/// Archidekt import text with the commander tagged, e.g. `1x Sol Ring (C21) 263 [Commander]`.
+pub fn archidekt_text(export_cards: &[ExportCard]) -> String {
+    export_cards
+        .iter()
+        .map(|export_card| {
+            let tag = match export_card.category {
+                ExportCategory::Commander => " [Commander]",
+                ExportCategory::Mainboard => "",
+            };
+            format!(
+                "{}x {}{}{tag}\n",
+                export_card.count,
+                export_card.name,
+                export_card.printing()
+            )
+        })
+        .collect()
+}
+
+/// CSV with Count, Name, Edition, Collector Number and Category columns as read by the Moxfield
+/// and Archidekt importers.
+pub fn export_csv(export_cards: &[ExportCard]) -> String {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for export_card in export_cards {
+        writer.serialize(export_card).expect("CSV serialize card");
+    }
+    String::from_utf8(writer.into_inner().expect("CSV writer flush")).expect("CSV UTF-8")
+}
+
+/// CSV of several decks like [`export_csv`] with a leading Deck column naming the commander of
+/// each card's deck, so all decks end up in one file with one header.
+pub fn export_decks_csv(export_lists: &[Vec<ExportCard>]) -> String {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer
+        .write_record([
+            "Deck",
+            "Count",
+            "Name",
+            "Edition",
+            "Collector Number",
+            "Category",
+        ])
+        .expect("CSV write header");
+    for export_cards in export_lists {
+        let deck = deck_name(export_cards);
+        for export_card in export_cards {
+            writer
+                .write_record([
+                    deck,
+                    &export_card.count.to_string(),
+                    &export_card.name,
+                    export_card.set.as_deref().unwrap_or_default(),
+                    export_card.collector_number.as_deref().unwrap_or_default(),
+                    match export_card.category {
+                        ExportCategory::Commander => "Commander",
+                        ExportCategory::Mainboard => "Mainboard",
+                    },
+                ])
+                .expect("CSV write card");
+        }
+    }
+    String::from_utf8(writer.into_inner().expect("CSV writer flush")).expect("CSV UTF-8")
+}
+
+/// Name of an exported deck, the name of its commander.
+fn deck_name(export_cards: &[ExportCard]) -> &str {
+    export_cards
+        .iter()
+        .find(|export_card| export_card.category == ExportCategory::Commander)
+        .map_or("", |export_card| export_card.name.as_str())
+}
+
+/// The lists in the export format, separated by a blank line. Several lists are exported as one
+/// CSV with a Deck column, and as MTG Arena decks named after their commander in an `About`
+/// section so every deck can be imported on its own.
+pub fn export_text(export_lists: &[Vec<ExportCard>], export_format: ExportFormat) -> String {
+    let several = export_lists.len() > 1;
+    if several && export_format == ExportFormat::Csv {
+        return export_decks_csv(export_lists);
+    }
+    let exports: Vec<String> = export_lists
+        .iter()
+        .map(|export_cards| match export_format {
+            ExportFormat::Arena if several => format!(
+                "About\nName {}\n\n{}",
+                deck_name(export_cards),
+                arena_text(export_cards)
+            ),
+            ExportFormat::Arena => arena_text(export_cards),
+            ExportFormat::Archidekt => archidekt_text(export_cards),
+            ExportFormat::Csv => export_csv(export_cards),
+        })
+        .collect();
+    exports.join("\n")
+}
@@ -1,21 +1,62 @@
 mod card_utils;
+pub mod allocation;
+pub mod analysis;
+pub mod budget;
 pub mod commander;
+pub mod config;
+pub mod deck;
+pub mod exclusion;
+pub mod export;
+pub mod mana_base;
+pub mod progress;
+pub mod purchase;
+pub mod report;
+pub mod statistics;
 pub mod storage;
+pub mod theme;
 
 use std::{collections::HashMap, path::PathBuf};
 
+use mongodb::Database;
 use scryfall::Card;
+use tracing::{debug, info, warn};
 
-use card_utils::{extract_card_colors, extract_oracle_text};
+use card_utils::{card_matches_keyword, extract_card_colors};
 
 use crate::{
-    commander::extract_catalogued_keywords,
-    storage::{read_deckbox_collection, update_oracle},
+    commander::{extract_catalogued_keywords, keyword_catalogs},
+    exclusion::Exclusions,
+    progress::{NoProgress, Progress},
+    storage::{
+        card_collection, connect, fill_oracle, owned_card_collection,
+        read_deckbox_collection_quantities, save_collection, OwnedCard, DEFAULT_DATABASE_URI,
+    },
 };
 
+/// Suggests commanders for the collection in the CSV file without storing it, the oracle
+/// database is filled first if it's empty. Use [`import_collection`] to store the collection.
 pub async fn commander_suggestions(csv_path: PathBuf) -> Vec<(Card, HashMap<String, Vec<Card>>)> {
-    let cards_database = update_oracle().await;
-    println!(
+    let cards_database = card_collection(&connect(DEFAULT_DATABASE_URI).await);
+    fill_oracle(&cards_database, &NoProgress).await;
+    let recognised_cards: Vec<Card> =
+        read_deckbox_collection_quantities(cards_database, csv_path, &NoProgress)
+            .await
+            .into_iter()
+            .map(|owned_card| owned_card.card)
+            .collect();
+    suggest_commanders(&recognised_cards, &Exclusions::default()).await
+}
+
+/// Updates the oracle database, reads the collection with the number of owned copies and stores
+/// it in place of the previously imported one.
+pub async fn import_collection(
+    database: &Database,
+    csv_path: PathBuf,
+    progress: &dyn Progress,
+) -> Vec<OwnedCard> {
+    let cards_database = card_collection(database);
+    fill_oracle(&cards_database, progress).await;
+    info!(
         "Imported about {} cards",
         cards_database
             .estimated_document_count(None)
@@ -23,38 +64,119 @@ pub async fn commander_suggestions(csv_path: PathBuf) -> Vec<(Card, HashMap<Stri
             .expect("Mongo estimated document count")
     );
 
-    let recognised_cards = read_deckbox_collection(cards_database, csv_path).await;
-    println!(
+    let recognised_cards =
+        read_deckbox_collection_quantities(cards_database, csv_path, progress).await;
+    info!(
         "Recognised {} cards from collection",
         &recognised_cards.len()
     );
+    save_collection(&owned_card_collection(database), &recognised_cards).await;
+    recognised_cards
+}
+
+pub async fn suggest_commanders(
+    recognised_cards: &[Card],
+    exclusions: &Exclusions,
+) -> Vec<(Card, HashMap<String, Vec<Card>>)> {
+    suggest_commanders_with_catalogs(recognised_cards, exclusions, &keyword_catalogs().await)
+}
 
-    let commanders = filter_commanders(&recognised_cards);
-    println!("Found {} possible commanders", commanders.len());
+/// Suggests commanders with keywords from the given catalogs instead of fetching them.
+pub fn suggest_commanders_with_catalogs(
+    recognised_cards: &[Card],
+    exclusions: &Exclusions,
+    catalogs: &[Vec<String>],
+) -> Vec<(Card, HashMap<String, Vec<Card>>)> {
+    let commanders = filter_commanders(recognised_cards, exclusions);
+    info!("Found {} possible commanders", commanders.len());
 
-    let commander_keywords = extract_catalogued_keywords(commanders);
+    let commander_keywords = extract_catalogued_keywords(commanders, catalogs);
     for (commander, keywords) in &commander_keywords {
-        println!("{}; {:#?}", commander.name, keywords);
+        debug!("{}; {:?}", commander.name, keywords);
     }
 
     let mut commander_compatible_cards =
-        find_compatible_cards(commander_keywords, &recognised_cards);
-    commander_compatible_cards.sort_unstable_by(|(_, cards1), (_, cards2)| {
+        find_compatible_cards(commander_keywords, recognised_cards, exclusions);
+    sort_by_card_count(&mut commander_compatible_cards);
+
+    commander_compatible_cards
+}
+
+/// Orders the suggestions by their number of compatible cards, most first.
+pub(crate) fn sort_by_card_count(suggestions: &mut [(Card, HashMap<String, Vec<Card>>)]) {
+    suggestions.sort_unstable_by(|(_, cards1), (_, cards2)| {
         cards2
             .values()
             .flatten()
             .count()
             .cmp(&cards1.values().flatten().count())
     });
+}
 
-    commander_compatible_cards
+/// Groups the collection by the keywords of a chosen commander without ranking all legendary
+/// creatures of the collection. The commander doesn't need to be part of the collection, an
+/// excluded commander gets no cards.
+pub async fn suggest_for_commander(
+    commander: &Card,
+    recognised_cards: &[Card],
+    exclusions: &Exclusions,
+) -> (Card, HashMap<String, Vec<Card>>) {
+    suggest_for_commander_with_catalogs(
+        commander,
+        recognised_cards,
+        exclusions,
+        &keyword_catalogs().await,
+    )
+}
+
+/// Groups the collection by the commander's keywords from the given catalogs instead of fetching
+/// them.
+pub fn suggest_for_commander_with_catalogs(
+    commander: &Card,
+    recognised_cards: &[Card],
+    exclusions: &Exclusions,
+    catalogs: &[Vec<String>],
+) -> (Card, HashMap<String, Vec<Card>>) {
+    if !(commander.type_line.contains("Legendary") && commander.type_line.contains("Creature")) {
+        warn!("{} is not a legendary creature", commander.name);
+    }
+    let commander_keywords = extract_catalogued_keywords(vec![commander.to_owned()], catalogs);
+    find_compatible_cards(commander_keywords, recognised_cards, exclusions)
+        .pop()
+        .expect("Compatible cards of commander")
+}
+
+/// Whether a suggested commander is one of the chosen ones, every commander is if none are chosen.
+pub fn is_selected(commander: &Card, commanders: &[String]) -> bool {
+    commanders.is_empty() || commanders.contains(&commander.name)
 }
 
-fn filter_commanders(cards: &[Card]) -> Vec<Card> {
+/// Fails with the chosen commanders none of the suggestions is for.
+pub fn check_selected(
+    suggestions: &[(Card, HashMap<String, Vec<Card>>)],
+    commanders: &[String],
+) -> Result<(), String> {
+    let unmatched: Vec<&str> = commanders
+        .iter()
+        .filter(|name| !suggestions.iter().any(|(commander, _)| &&commander.name == name))
+        .map(String::as_str)
+        .collect();
+    if unmatched.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "No suggestion for {}: commanders have to be legendary creatures of the collection \
+         which are not excluded",
+        unmatched.join("; ")
+    ))
+}
+
+fn filter_commanders(cards: &[Card], exclusions: &Exclusions) -> Vec<Card> {
     cards
         .iter()
         .filter(|card| card.type_line.contains("Legendary"))
         .filter(|card| card.type_line.contains("Creature"))
+        .filter(|card| !exclusions.excludes(card))
         .map(|commander| commander.to_owned())
         .collect()
 }
@@ -62,35 +184,43 @@ fn filter_commanders(cards: &[Card]) -> Vec<Card> {
 fn find_compatible_cards(
     commander_keywords: Vec<(Card, Vec<String>)>,
     collection: &[Card],
+    exclusions: &Exclusions,
 ) -> Vec<(Card, HashMap<String, Vec<Card>>)> {
     commander_keywords
         .iter()
         .map(|(commander, keywords)| {
             (
                 commander.to_owned(),
-                match_colors_and_keywords(commander, keywords, collection),
+                match_colors_and_keywords(commander, keywords, collection, exclusions),
             )
         })
         .collect()
 }
 
-fn match_colors_and_keywords(
+/// Groups the cards of the collection within the commander's colours by the first keyword they
+/// match. Excluded cards are left out, an excluded commander gets no cards at all.
+pub(crate) fn match_colors_and_keywords(
     commander: &Card,
     keywords: &[String],
     collection: &[Card],
+    exclusions: &Exclusions,
 ) -> HashMap<String, Vec<Card>> {
     let mut keywords_cards = HashMap::new();
+    if exclusions.excludes(commander) {
+        return keywords_cards;
+    }
     collection
         .iter()
+        .filter(|card| !exclusions.excludes(card))
         .filter(|card| {
             extract_card_colors(card)
                 .iter()
                 .all(|color| commander.color_identity.contains(color))
         })
         .filter_map(|card| {
-            let keyword = keywords.iter().find(|&keyword| {
-                card.type_line.contains(keyword) || extract_oracle_text(card).contains(keyword)
-            });
+            let keyword = keywords
+                .iter()
+                .find(|&keyword| card_matches_keyword(card, keyword));
             keyword.map(|keyword| (keyword.to_owned(), card.to_owned()))
         })
         .for_each(|(keyword, card)| {
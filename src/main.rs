@@ -1,29 +1,888 @@
-use std::path::PathBuf;
+use std::{
+    io::{self, stderr, stdout, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
 
-use clap::Parser;
-use color_eyre::eyre::Result;
-use mtg_commander_suggestions::commander_suggestions;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use color_eyre::eyre::{eyre, Report, Result};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use mongodb::Database;
+use mtg_commander_suggestions::{
+    allocation::{allocate_copies, Allocation},
+    analysis::{analyze_deck, DeckAnalysis},
+    budget::{Budget, Currency},
+    check_selected,
+    config::{find_config_file, read_config, Settings},
+    deck::{build_decks, Deck, DECK_SIZE, DEFAULT_LAND_COUNT},
+    exclusion::{Exclusions, PowerLevel},
+    export::{deck_export_cards, export_text, suggestion_export_cards, ExportCard, ExportFormat},
+    import_collection, is_selected,
+    mana_base::basic_land_name,
+    progress::{Progress, ProgressUnit},
+    purchase::{cards_to_buy, PurchaseSuggestion, DEFAULT_PURCHASE_LIMIT},
+    report::{commander_reports, csv_report, html_report, markdown_report},
+    statistics::{card_statistics, CardStatistics},
+    storage::{
+        card_collection, clear_oracle, connect, find_card_by_name, load_collection,
+        load_owned_cards, owned_card_collection, owned_printings, read_decklist, refresh_oracle,
+        store_status, StoreStatus, DEFAULT_DATABASE_URI,
+    },
+    suggest_commanders, suggest_for_commander,
+    theme::{add_themes, ThemeRule},
+};
+use scryfall::{Card, ScryfallClient};
+use serde::Serialize;
+use tracing::warn;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Indented names
+    Text,
+    /// One JSON array, or object for single results
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// A self-contained HTML page with card images, suggest only
+    Html,
+    /// Markdown tables per commander, suggest only
+    Markdown,
+    /// One CSV row per entry, for suggest per commander, theme and card
+    Csv,
+}
 
 #[derive(Parser)]
 struct Arguments {
-    csv_path: PathBuf,
+    /// Config file, by default mtg-commander-suggestions.toml in the working directory or the
+    /// user's config directory
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Profile of the config file to use on top of its default settings
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// MongoDB connection string of the store for oracle cards and the imported collection
+    /// [default: mongodb://localhost:27017]
+    #[arg(long, global = true)]
+    database_uri: Option<String>,
+    /// Output format, not every command supports every format [default: text]
+    #[arg(long, global = true, value_enum)]
+    output: Option<OutputFormat>,
+    /// Leave this card out of suggestions and decks
+    #[arg(long, global = true, value_name = "NAME")]
+    exclude: Vec<String>,
+    /// Leave cards matching this Scryfall search out, e.g. 'o:"extra turn"', 't:land -c:g' or
+    /// 'kw:cascade or is:gamechanger'
+    #[arg(long, global = true, value_name = "QUERY")]
+    exclude_query: Vec<String>,
+    /// File of theme rules grouping the cards of Scryfall searches next to the keywords
+    #[arg(long, global = true, value_name = "PATH")]
+    theme_file: Vec<PathBuf>,
+    /// Power level preset, casual excludes the Game Changers [default: unrestricted]
+    #[arg(long, global = true)]
+    power_level: Option<PowerLevel>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage the local copy of the Scryfall oracle cards
+    #[command(subcommand)]
+    Db(DbCommand),
+    /// Import and inspect the collection
+    #[command(subcommand)]
+    Collection(CollectionCommand),
+    /// Suggest commanders with groups of matching cards from the imported collection
+    Suggest(SuggestArguments),
+    /// Build or analyse decks
+    #[command(subcommand)]
+    Deck(DeckCommand),
+    /// Look up cards in the oracle database
+    #[command(subcommand)]
+    Card(CardCommand),
+    /// Print the suggestions or built decks as lists for deck building sites
+    Export(ExportArguments),
+}
+
+#[derive(Subcommand)]
+enum DbCommand {
+    /// Download the current oracle cards, replacing the stored ones
+    Update,
+    /// Show the number of stored oracle cards and collection cards
+    Status,
+    /// Delete the stored oracle cards
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum CollectionCommand {
+    /// Read a csv export of a deckbox.org collection (any csv with a 'Name' column) into the store,
+    /// replacing the previously imported collection
+    Import { csv_path: PathBuf },
+    /// List the imported cards with their number of owned copies
+    List,
+    /// Show mana curve, colour pips and card types of the imported collection
+    Stats,
+}
+
+#[derive(Args)]
+struct SuggestArguments {
+    /// Only group the collection for this commander, which doesn't need to be owned
+    #[arg(long)]
+    commander: Option<String>,
+    /// Suggest unowned cards to buy for every suggested commander
+    #[arg(long)]
+    buy: bool,
+    /// Maximum number of cards to buy per commander [default: 20]
+    #[arg(long)]
+    buy_limit: Option<usize>,
+    #[command(flatten)]
+    budget: BudgetArguments,
+}
+
+#[derive(Subcommand)]
+enum DeckCommand {
+    /// Build a deck with a mana base for every suggested commander
+    Build(DeckArguments),
+    /// Analyse an existing decklist (text or CSV) against the collection
+    Analyze { decklist_path: PathBuf },
+}
+
+#[derive(Args)]
+struct DeckArguments {
+    /// Build decks for these commanders only, sharing the owned copies of the collection
+    #[arg(long = "commander", value_name = "COMMANDER")]
+    commanders: Vec<String>,
+    /// Number of lands in built decks [default: 37]
+    #[arg(long)]
+    land_count: Option<usize>,
+    #[command(flatten)]
+    budget: BudgetArguments,
+}
+
+impl DeckArguments {
+    fn land_count(&self, settings: &Settings) -> usize {
+        self.land_count
+            .or(settings.land_count)
+            .unwrap_or(DEFAULT_LAND_COUNT)
+    }
+}
+
+#[derive(Subcommand)]
+enum CardCommand {
+    /// Show a card of the oracle database
+    Show { name: String },
+}
+
+#[derive(Args)]
+struct ExportArguments {
+    /// Format of the lists: arena, archidekt or csv [default: arena]
+    #[arg(long)]
+    format: Option<ExportFormat>,
+    /// Export built decks instead of all matched cards
+    #[arg(long)]
+    deck: bool,
+    #[command(flatten)]
+    deck_arguments: DeckArguments,
+}
+
+#[derive(Args)]
+struct BudgetArguments {
+    /// Total price limit for built decks and for the cards to buy per commander
+    #[arg(long = "budget", value_name = "BUDGET")]
+    total: Option<f32>,
+    /// Price limit for a single card
+    #[arg(long)]
+    card_budget: Option<f32>,
+    /// Currency of prices and budgets: usd, eur or tix [default: usd]
+    #[arg(long)]
+    currency: Option<Currency>,
+    /// Use the cheapest printing of every card from Scryfall for prices
+    #[arg(long)]
+    cheapest_printings: bool,
+    /// Preferred language of printings from Scryfall, e.g. de, repeatable in order of preference
+    #[arg(long = "language", value_name = "LANGUAGE")]
+    languages: Vec<String>,
+}
+
+impl BudgetArguments {
+    fn budget(&self, settings: &Settings) -> Budget {
+        Budget {
+            currency: self.currency.or(settings.currency).unwrap_or_default(),
+            total_limit: self.total.or(settings.budget),
+            card_limit: self.card_budget.or(settings.card_budget),
+            cheapest_printings: self.cheapest_printings
+                || settings.cheapest_printings.unwrap_or_default(),
+            languages: if self.languages.is_empty() {
+                settings.languages.to_owned()
+            } else {
+                self.languages.to_owned()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CollectionEntry<'a> {
+    name: &'a str,
+    oracle_id: Uuid,
+    count: usize,
+}
+
+/// Writes log lines to stderr above the progress bars.
+struct LogWriter(MultiProgress);
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.suspend(|| stderr().write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        stderr().flush()
+    }
+}
+
+/// Shows the steps of the library as progress bars on stderr.
+struct BarProgress {
+    bars: MultiProgress,
+    bar: Mutex<Option<(ProgressBar, ProgressUnit)>>,
+}
+
+impl BarProgress {
+    fn new(bars: MultiProgress) -> Self {
+        BarProgress {
+            bars,
+            bar: Mutex::new(None),
+        }
+    }
+}
+
+impl Progress for BarProgress {
+    fn start(&self, step: &str, unit: ProgressUnit) {
+        let template = match unit {
+            ProgressUnit::Items => "{spinner} {msg} {pos}",
+            ProgressUnit::Bytes => "{spinner} {msg} {bytes}",
+        };
+        let bar = self.bars.add(ProgressBar::new_spinner());
+        bar.set_style(ProgressStyle::with_template(template).expect("Progress template"));
+        bar.set_message(step.to_owned());
+        bar.enable_steady_tick(Duration::from_millis(100));
+        *self.bar.lock().expect("Progress bar lock") = Some((bar, unit));
+    }
+
+    fn update(&self, position: u64, total: Option<u64>) {
+        if let Some((bar, unit)) = &*self.bar.lock().expect("Progress bar lock") {
+            if let Some(total) = total {
+                if bar.length() != Some(total) {
+                    let template = match unit {
+                        ProgressUnit::Items => "{msg} [{bar:40}] {pos}/{len} ({eta})",
+                        ProgressUnit::Bytes => "{msg} [{bar:40}] {bytes}/{total_bytes} ({eta})",
+                    };
+                    bar.set_style(
+                        ProgressStyle::with_template(template)
+                            .expect("Progress template")
+                            .progress_chars("=> "),
+                    );
+                    bar.set_length(total);
+                }
+            }
+            bar.set_position(position);
+        }
+    }
+
+    fn finish(&self) {
+        if let Some((bar, _)) = self.bar.lock().expect("Progress bar lock").take() {
+            bar.finish_and_clear();
+            self.bars.remove(&bar);
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
+    let bars = MultiProgress::new();
+    let log_bars = bars.clone();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .with_writer(move || LogWriter(log_bars.clone()))
+        .with_target(false)
+        .without_time()
+        .init();
+    let progress = BarProgress::new(bars);
     let arguments = Arguments::parse();
+    let settings =
+        load_settings(arguments.config.as_deref(), arguments.profile.as_deref())?.merge(Settings {
+            exclude: arguments.exclude,
+            exclude_queries: arguments.exclude_query,
+            power_level: arguments.power_level,
+            theme_files: arguments.theme_file,
+            ..Default::default()
+        });
+    let exclusions = settings.exclusions().map_err(|error| eyre!(error))?;
+    let themes = settings.theme_rules().map_err(|error| eyre!(error))?;
+    let mut scryfall_client = ScryfallClient::builder().user_agent(concat!(
+        env!("CARGO_PKG_NAME"),
+        "/",
+        env!("CARGO_PKG_VERSION")
+    ));
+    if let Some(scryfall_url) = &settings.scryfall_url {
+        scryfall_client = scryfall_client.base_url(scryfall_url);
+    }
+    scryfall_client.build()?.set_default();
+    let database = connect(
+        arguments
+            .database_uri
+            .as_deref()
+            .or(settings.database_uri.as_deref())
+            .unwrap_or(DEFAULT_DATABASE_URI),
+    )
+    .await;
+    let output = match (arguments.output, &settings.output) {
+        (Some(output), _) => output,
+        (None, Some(output)) => {
+            OutputFormat::from_str(output, true).map_err(|error| eyre!(error))?
+        }
+        (None, None) => OutputFormat::Text,
+    };
+
+    match arguments.command {
+        Command::Db(command) => db(command, &database, output, &progress).await,
+        Command::Collection(command) => collection(command, &database, output, &progress).await,
+        Command::Suggest(suggest_arguments) => {
+            suggest(
+                suggest_arguments,
+                &database,
+                &settings,
+                &exclusions,
+                &themes,
+                output,
+            )
+            .await
+        }
+        Command::Deck(command) => {
+            require_text(output)?;
+            deck(command, &database, &settings, &exclusions, &themes).await
+        }
+        Command::Card(CardCommand::Show { name }) => {
+            let card = match find_card_by_name(&card_collection(&database), &name).await {
+                Some(card) => card,
+                None => return Err(card_not_found(&name).await),
+            };
+            match output {
+                OutputFormat::Text => print_card(&card),
+                _ => print_value(output, &card)?,
+            }
+            Ok(())
+        }
+        Command::Export(export_arguments) => {
+            require_text(output)?;
+            export(export_arguments, &database, &settings, &exclusions, &themes).await
+        }
+    }
+}
+
+async fn db(
+    command: DbCommand,
+    database: &Database,
+    output: OutputFormat,
+    progress: &BarProgress,
+) -> Result<()> {
+    match command {
+        DbCommand::Update => {
+            require_text(output)?;
+            refresh_oracle(database, progress).await;
+            print_store_status(&store_status(database).await);
+        }
+        DbCommand::Status => match output {
+            OutputFormat::Text => print_store_status(&store_status(database).await),
+            _ => print_value(output, &store_status(database).await)?,
+        },
+        DbCommand::Clear => {
+            require_text(output)?;
+            clear_oracle(&card_collection(database)).await;
+            println!("Cleared oracle cards");
+        }
+    }
+    Ok(())
+}
+
+async fn collection(
+    command: CollectionCommand,
+    database: &Database,
+    output: OutputFormat,
+    progress: &BarProgress,
+) -> Result<()> {
+    match command {
+        CollectionCommand::Import { csv_path } => {
+            require_text(output)?;
+            let collection = import_collection(database, csv_path, progress).await;
+            println!(
+                "Stored {} cards, {} copies",
+                collection.len(),
+                collection
+                    .iter()
+                    .map(|owned_card| owned_card.count)
+                    .sum::<usize>()
+            );
+        }
+        CollectionCommand::List => {
+            let collection = stored_collection(database).await?;
+            match output {
+                OutputFormat::Text => {
+                    for (card, count) in &collection {
+                        println!("{count} {}", card.name);
+                    }
+                }
+                _ => print_values(
+                    output,
+                    &collection
+                        .iter()
+                        .map(|(card, count)| CollectionEntry {
+                            name: &card.name,
+                            oracle_id: card.oracle_id,
+                            count: *count,
+                        })
+                        .collect::<Vec<_>>(),
+                )?,
+            }
+        }
+        CollectionCommand::Stats => {
+            let collection = stored_collection(database).await?;
+            let statistics = card_statistics(collection.iter().map(|(card, _)| card));
+            match output {
+                OutputFormat::Text => print_statistics(&statistics),
+                _ => print_value(output, &statistics)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn suggest(
+    arguments: SuggestArguments,
+    database: &Database,
+    settings: &Settings,
+    exclusions: &Exclusions,
+    themes: &[ThemeRule],
+    output: OutputFormat,
+) -> Result<()> {
+    if arguments.buy {
+        require_text(output)?;
+    }
+    let collection = exclusions.playable_cards(stored_collection(database).await?);
+    let cards: Vec<Card> = collection.iter().map(|(card, _)| card.to_owned()).collect();
+    let mut commander_compatible_cards = match &arguments.commander {
+        Some(name) => {
+            let commander = match find_card_by_name(&card_collection(database), name).await {
+                Some(commander) => commander,
+                None => return Err(card_not_found(name).await),
+            };
+            if exclusions.excludes(&commander) {
+                return Err(eyre!("{name} is excluded"));
+            }
+            vec![suggest_for_commander(&commander, &cards, exclusions).await]
+        }
+        None => suggest_commanders(&cards, exclusions).await,
+    };
+    add_themes(&mut commander_compatible_cards, themes, &cards, exclusions);
+
+    if output != OutputFormat::Text {
+        let reports = commander_reports(&commander_compatible_cards, &collection);
+        match output {
+            OutputFormat::Html => print!("{}", html_report(&reports)),
+            OutputFormat::Markdown => print!("{}", markdown_report(&reports)),
+            OutputFormat::Csv => print!("{}", csv_report(&reports)),
+            _ => print_values(output, &reports)?,
+        }
+        return Ok(());
+    }
 
-    let commander_compatible_cards = commander_suggestions(arguments.csv_path).await;
+    let budget = arguments.budget.budget(settings);
+    let buy_limit = arguments
+        .buy_limit
+        .or(settings.buy_limit)
+        .unwrap_or(DEFAULT_PURCHASE_LIMIT);
+    let cards_database = card_collection(database);
     for (commander, compatible_cards) in &commander_compatible_cards {
         println!("{}", commander.name);
+        print_statistics(&card_statistics(compatible_cards.values().flatten()));
         for (keyword, cards) in compatible_cards {
             println!("\t{keyword}");
             for card in cards {
                 println!("\t\t{}", card.name);
             }
         }
+        if arguments.buy {
+            let keywords: Vec<String> = compatible_cards
+                .keys()
+                .filter(|keyword| !themes.iter().any(|theme| theme.name == **keyword))
+                .cloned()
+                .collect();
+            print_purchases(
+                &cards_to_buy(
+                    &cards_database,
+                    commander,
+                    &keywords,
+                    &cards,
+                    exclusions,
+                    &budget,
+                    buy_limit,
+                )
+                .await,
+                &budget,
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn deck(
+    command: DeckCommand,
+    database: &Database,
+    settings: &Settings,
+    exclusions: &Exclusions,
+    themes: &[ThemeRule],
+) -> Result<()> {
+    let collection = exclusions.playable_cards(stored_collection(database).await?);
+    let cards: Vec<Card> = collection.iter().map(|(card, _)| card.to_owned()).collect();
+    match command {
+        DeckCommand::Build(arguments) => {
+            let mut budget = arguments.budget.budget(settings);
+            budget.owned_printings =
+                owned_printings(&load_owned_cards(&owned_card_collection(database)).await);
+            let decks = build_decks(
+                &collection,
+                &arguments.commanders,
+                arguments.land_count(settings),
+                &mut budget,
+                exclusions,
+                themes,
+            )
+            .await
+            .map_err(|error| eyre!(error))?;
+            if arguments.commanders.is_empty() {
+                for deck in &decks {
+                    println!("{}", deck.commander.name);
+                    print_deck(deck, &budget);
+                }
+            } else {
+                print_allocation(&allocate_copies(decks, &collection), &budget);
+            }
+        }
+        DeckCommand::Analyze { decklist_path } => {
+            let decklist = read_decklist(card_collection(database), decklist_path).await;
+            let commander = decklist
+                .commander
+                .ok_or_else(|| eyre!("No commander found in decklist"))?;
+            print_analysis(&analyze_deck(&commander, &decklist.cards, &cards, exclusions).await);
+        }
     }
+    Ok(())
+}
 
+async fn export(
+    arguments: ExportArguments,
+    database: &Database,
+    settings: &Settings,
+    exclusions: &Exclusions,
+    themes: &[ThemeRule],
+) -> Result<()> {
+    let collection = exclusions.playable_cards(stored_collection(database).await?);
+    let owned_printings =
+        owned_printings(&load_owned_cards(&owned_card_collection(database)).await);
+    let export_format = match (arguments.format, &settings.export_format) {
+        (Some(export_format), _) => export_format,
+        (None, Some(export_format)) => export_format
+            .parse::<ExportFormat>()
+            .map_err(|error| eyre!(error))?,
+        (None, None) => ExportFormat::default(),
+    };
+    let deck_arguments = &arguments.deck_arguments;
+    let export_lists: Vec<Vec<ExportCard>> = if arguments.deck {
+        let mut budget = deck_arguments.budget.budget(settings);
+        budget.owned_printings = owned_printings.clone();
+        let mut decks = build_decks(
+            &collection,
+            &deck_arguments.commanders,
+            deck_arguments.land_count(settings),
+            &mut budget,
+            exclusions,
+            themes,
+        )
+        .await
+        .map_err(|error| eyre!(error))?;
+        if !deck_arguments.commanders.is_empty() {
+            decks = allocate_copies(decks, &collection).decks;
+            for deck in decks.iter().filter(|deck| deck.missing_cards() > 0) {
+                warn!(
+                    "{} is {} cards short of {DECK_SIZE} after sharing the collection",
+                    deck.commander.name,
+                    deck.missing_cards()
+                );
+            }
+        }
+        decks
+            .iter()
+            .map(|deck| deck_export_cards(deck, &owned_printings))
+            .collect()
+    } else {
+        let cards: Vec<Card> = collection.iter().map(|(card, _)| card.to_owned()).collect();
+        let mut suggestions = suggest_commanders(&cards, exclusions).await;
+        add_themes(&mut suggestions, themes, &cards, exclusions);
+        check_selected(&suggestions, &deck_arguments.commanders).map_err(|error| eyre!(error))?;
+        suggestions
+            .iter()
+            .filter(|(commander, _)| is_selected(commander, &deck_arguments.commanders))
+            .map(|(commander, compatible_cards)| {
+                suggestion_export_cards(commander, compatible_cards, &owned_printings)
+            })
+            .collect()
+    };
+    print!("{}", export_text(&export_lists, export_format));
     Ok(())
 }
+
+fn load_settings(config_path: Option<&Path>, profile: Option<&str>) -> Result<Settings> {
+    let config = match config_path.map(Path::to_path_buf).or_else(find_config_file) {
+        Some(config_path) => read_config(&config_path).map_err(|error| eyre!(error))?,
+        None if profile.is_some() => return Err(eyre!("No config file found for the profile")),
+        None => return Ok(Settings::default()),
+    };
+    config.settings(profile).map_err(|error| eyre!(error))
+}
+
+async fn stored_collection(database: &Database) -> Result<Vec<(Card, usize)>> {
+    let collection = load_collection(&owned_card_collection(database)).await;
+    if collection.is_empty() {
+        return Err(eyre!(
+            "No collection imported, run `collection import <CSV_PATH>` first"
+        ));
+    }
+    Ok(collection)
+}
+
+/// The error for a card name missing from the database, suggesting the card
+/// names Scryfall completes it to.
+async fn card_not_found(name: &str) -> Report {
+    match Card::autocomplete_async(name).await {
+        Ok(names) if !names.is_empty() => {
+            eyre!("{name} not found, did you mean {}?", names.join(", "))
+        }
+        _ => eyre!("{name} not found"),
+    }
+}
+
+fn require_text(output: OutputFormat) -> Result<()> {
+    match output {
+        OutputFormat::Text => Ok(()),
+        _ => Err(eyre!("This command only supports text output")),
+    }
+}
+
+fn print_value<T: Serialize>(output: OutputFormat, value: &T) -> Result<()> {
+    match output {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(stdout(), value)?;
+            println!();
+        }
+        OutputFormat::Ndjson => println!("{}", serde_json::to_string(value)?),
+        _ => {
+            return Err(eyre!(
+                "This command only supports text, json and ndjson output"
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn print_values<T: Serialize>(output: OutputFormat, values: &[T]) -> Result<()> {
+    match output {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(stdout(), values)?;
+            println!();
+        }
+        OutputFormat::Ndjson => {
+            for value in values {
+                println!("{}", serde_json::to_string(value)?);
+            }
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(stdout());
+            for value in values {
+                writer.serialize(value)?;
+            }
+            writer.flush()?;
+        }
+        _ => {
+            return Err(eyre!(
+                "This command only supports text, json, ndjson and csv output"
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn print_store_status(status: &StoreStatus) {
+    println!("Oracle cards: {}", status.oracle_cards);
+    println!(
+        "Collection: {} cards, {} copies",
+        status.collection_cards, status.owned_copies
+    );
+}
+
+fn print_card(card: &Card) {
+    println!("{}", card.name);
+    if let Some(mana_cost) = &card.mana_cost {
+        println!("\t{mana_cost}");
+    }
+    println!("\t{}", card.type_line);
+    if let Some(oracle_text) = &card.oracle_text {
+        for line in oracle_text.lines() {
+            println!("\t{line}");
+        }
+    }
+    let price = |price: &Option<String>, currency: &str| {
+        price
+            .as_ref()
+            .map_or("-".to_owned(), |price| format!("{price}{currency}"))
+    };
+    println!(
+        "\tPrice: {} / {}, EDHREC rank {}",
+        price(&card.prices.usd, "$"),
+        price(&card.prices.eur, "€"),
+        card.edhrec_rank
+            .map_or("-".to_owned(), |rank| rank.to_string())
+    );
+    println!("\t{}", card.scryfall_uri);
+}
+
+fn print_deck(deck: &Deck, budget: &Budget) {
+    println!("\tSpells ({})", deck.spells.len());
+    for card in &deck.spells {
+        println!("\t\t{}", card.name);
+    }
+    println!("\tLands ({})", deck.lands.land_count());
+    for land in &deck.lands.nonbasic_lands {
+        println!("\t\t{}", land.name);
+    }
+    for (color, count) in &deck.lands.basic_lands {
+        println!("\t\t{} {}", count, basic_land_name(*color));
+    }
+    println!(
+        "\tTotal value: {:.2} {}",
+        deck.total_value(budget),
+        budget.currency
+    );
+}
+
+fn print_allocation(allocation: &Allocation, budget: &Budget) {
+    for deck in &allocation.decks {
+        println!("{}", deck.commander.name);
+        print_deck(deck, budget);
+        if deck.missing_cards() > 0 {
+            println!("\t{} cards short of {DECK_SIZE}", deck.missing_cards());
+        }
+    }
+    if !allocation.conflicts.is_empty() {
+        println!("Conflicts");
+    }
+    for conflict in &allocation.conflicts {
+        let contenders: Vec<String> = conflict
+            .contenders
+            .iter()
+            .map(|(commander, score)| format!("{} ({score})", commander.name))
+            .collect();
+        println!(
+            "\t{}: {} owned, wanted by {}",
+            conflict.card.name,
+            conflict.owned,
+            contenders.join(", ")
+        );
+        for (commander, _) in conflict.suggested_decks() {
+            println!("\t\tsuggested for {}", commander.name);
+        }
+        for (commander, _) in conflict.short_decks() {
+            println!("\t\tmissing from {}", commander.name);
+        }
+    }
+}
+
+fn print_analysis(analysis: &DeckAnalysis) {
+    println!("{}", analysis.commander.name);
+    println!("\tKeywords: {}", analysis.keywords.join(", "));
+    println!("\tMissing cards from the collection");
+    for (keyword, cards) in &analysis.missing_keyword_cards {
+        println!("\t\t{keyword}");
+        for card in cards {
+            println!("\t\t\t{}", card.name);
+        }
+    }
+    println!("\tCards not matching any keyword");
+    for card in &analysis.off_theme_cards {
+        println!("\t\t{}", card.name);
+    }
+    println!("\tSuggested swaps");
+    for swap in &analysis.swaps {
+        println!(
+            "\t\t-{} +{} (synergy {})",
+            swap.remove.name, swap.add.name, swap.synergy
+        );
+    }
+}
+
+fn print_purchases(purchases: &[PurchaseSuggestion], budget: &Budget) {
+    println!("\tCards to buy");
+    for purchase in purchases {
+        let price = |price: Option<f32>, currency: &str| {
+            price.map_or("-".to_owned(), |price| format!("{price:.2}{currency}"))
+        };
+        println!(
+            "\t\t{} ({} / {}, EDHREC rank {})",
+            purchase.card.name,
+            price(purchase.usd(), "$"),
+            price(purchase.eur(), "€"),
+            purchase
+                .card
+                .edhrec_rank
+                .map_or("-".to_owned(), |rank| rank.to_string())
+        );
+    }
+    println!(
+        "\t\tTotal {:.2} {}",
+        budget.total(purchases.iter().map(|purchase| &purchase.card)),
+        budget.currency
+    );
+}
+
+fn print_statistics(statistics: &CardStatistics) {
+    let mana_curve: Vec<String> = statistics
+        .mana_curve
+        .iter()
+        .map(|(mana_value, count)| format!("{mana_value}: {count}"))
+        .collect();
+    println!("\tMana curve: {}", mana_curve.join(", "));
+    println!(
+        "\tAverage mana value: {:.2}, creatures: {}, lands: {}",
+        statistics.average_mana_value, statistics.creature_count, statistics.land_count
+    );
+    let mut color_pips: Vec<String> = statistics
+        .color_pips
+        .iter()
+        .map(|(color, count)| format!("{color}: {count}"))
+        .collect();
+    color_pips.sort();
+    println!("\tColour pips: {}", color_pips.join(", "));
+    let card_types: Vec<String> = statistics
+        .card_types
+        .iter()
+        .map(|(card_type, count)| format!("{card_type}: {count}"))
+        .collect();
+    println!("\tCard types: {}", card_types.join(", "));
+}
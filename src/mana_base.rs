@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use scryfall::{card::Color, search::prelude::CardIs, Card};
+
+use crate::{
+    budget::Budget,
+    card_utils::{extract_mana_costs, extract_oracle_text, is_land},
+};
+
+const BASIC_LAND_TYPES: [(&str, Color); 5] = [
+    ("Plains", Color::White),
+    ("Island", Color::Blue),
+    ("Swamp", Color::Black),
+    ("Mountain", Color::Red),
+    ("Forest", Color::Green),
+];
+
+#[derive(Debug, Clone)]
+pub struct ManaBase {
+    pub nonbasic_lands: Vec<Card>,
+    pub basic_lands: Vec<(Color, usize)>,
+}
+
+impl ManaBase {
+    pub fn land_count(&self) -> usize {
+        self.nonbasic_lands.len()
+            + self
+                .basic_lands
+                .iter()
+                .map(|(_, count)| count)
+                .sum::<usize>()
+    }
+}
+
+pub fn basic_land_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "Plains",
+        Color::Blue => "Island",
+        Color::Black => "Swamp",
+        Color::Red => "Mountain",
+        Color::Green => "Forest",
+        Color::Colorless => "Wastes",
+    }
+}
+
+/// Counts the coloured mana symbols of all spells. Hybrid symbols count towards each of their
+/// colours, Phyrexian and two-generic hybrid symbols towards their only colour.
+pub fn color_pips<'a>(spells: impl IntoIterator<Item = &'a Card>) -> HashMap<Color, usize> {
+    let mut pips = HashMap::new();
+    for cost in spells.into_iter().flat_map(extract_mana_costs) {
+        for (color, count) in cost.pips() {
+            *pips.entry(color).or_insert(0) += count;
+        }
+    }
+    pips
+}
+
+/// Builds a land base of `land_count` lands for the commander. Owned nonbasic lands producing
+/// colours the spells ask for are used first as far as the budget allows after `spent`, the
+/// remaining slots are filled with basic lands in proportion to the colour pips of the spells.
+pub fn generate_mana_base(
+    commander: &Card,
+    spells: &[Card],
+    collection: &[Card],
+    land_count: usize,
+    budget: &Budget,
+    mut spent: f32,
+) -> ManaBase {
+    let mut pips = color_pips(spells);
+    pips.retain(|color, _| commander.color_identity.contains(color));
+
+    let mut nonbasic_lands: Vec<(usize, &Card)> = collection
+        .iter()
+        .filter(|card| is_land(card) && !card.type_line.contains("Basic"))
+        .filter(|land| {
+            land.color_identity
+                .iter()
+                .all(|color| commander.color_identity.contains(color))
+        })
+        .filter_map(|land| {
+            let colors = land_colors(land);
+            let demand = colors
+                .iter()
+                .filter_map(|color| pips.get(color))
+                .sum::<usize>();
+            let fits = if commander.color_identity.is_empty() {
+                land.produced_mana.is_some()
+            } else {
+                demand > 0
+            };
+            fits.then_some((demand, land))
+        })
+        .collect();
+    nonbasic_lands.sort_by(|(demand1, land1), (demand2, land2)| {
+        demand2
+            .cmp(demand1)
+            .then_with(|| cycle_rank(land1).cmp(&cycle_rank(land2)))
+            .then_with(|| land1.name.cmp(&land2.name))
+    });
+    nonbasic_lands.dedup_by(|(_, land1), (_, land2)| land1.oracle_id == land2.oracle_id);
+    let nonbasic_lands = budget.select(
+        nonbasic_lands.into_iter().map(|(_, land)| land),
+        &mut spent,
+        land_count,
+    );
+
+    let basic_count = land_count - nonbasic_lands.len();
+    ManaBase {
+        basic_lands: distribute_basics(commander, &pips, basic_count),
+        nonbasic_lands,
+    }
+}
+
+/// Colours a land can provide: its produced mana, or for lands fetching basic land types the
+/// colours of those types.
+pub fn land_colors(land: &Card) -> Vec<Color> {
+    match &land.produced_mana {
+        Some(produced_mana) => produced_mana.to_owned(),
+        None if land_cycle(land) == Some(CardIs::FetchLand) => {
+            let oracle_text = extract_oracle_text(land);
+            BASIC_LAND_TYPES
+                .iter()
+                .filter(|(land_type, _)| oracle_text.contains(land_type))
+                .map(|(_, color)| *color)
+                .collect()
+        }
+        None => vec![],
+    }
+}
+
+/// Recognises the common land cycles from their type line and rules text.
+pub fn land_cycle(land: &Card) -> Option<CardIs> {
+    let oracle_text = extract_oracle_text(land).to_lowercase();
+    let basic_types = BASIC_LAND_TYPES
+        .iter()
+        .filter(|(land_type, _)| land.type_line.contains(land_type))
+        .count();
+    let enters_tapped = oracle_text.contains("enters the battlefield tapped")
+        || oracle_text.contains("enters tapped");
+
+    if oracle_text.contains("sacrifice") && oracle_text.contains("search your library for") {
+        Some(CardIs::FetchLand)
+    } else if basic_types >= 3 && oracle_text.contains("cycling") {
+        Some(CardIs::TricycleLand)
+    } else if basic_types == 2 && oracle_text.contains("cycling") {
+        Some(CardIs::BicycleLand)
+    } else if basic_types == 2 && oracle_text.contains("pay 2 life") {
+        Some(CardIs::ShockLand)
+    } else if basic_types == 2 && !enters_tapped {
+        Some(CardIs::DualLand)
+    } else if oracle_text.contains("two or more basic lands") {
+        Some(CardIs::BattleLand)
+    } else if oracle_text.contains("two or fewer other lands") {
+        Some(CardIs::FastLand)
+    } else if oracle_text.contains("unless you control a") {
+        Some(CardIs::CheckLand)
+    } else if oracle_text.contains("reveal a") && enters_tapped {
+        Some(CardIs::ShadowLand)
+    } else if oracle_text.contains("deals 1 damage to you") {
+        Some(CardIs::PainLand)
+    } else if oracle_text.contains("return a land you control") {
+        Some(CardIs::BounceLand)
+    } else if enters_tapped && oracle_text.contains("scry 1") {
+        Some(CardIs::ScryLand)
+    } else if enters_tapped && oracle_text.contains("you gain 1 life") {
+        Some(CardIs::GainLand)
+    } else if enters_tapped && land.produced_mana.as_ref().map_or(0, Vec::len) == 3 {
+        Some(CardIs::TriLand)
+    } else {
+        None
+    }
+}
+
+/// Lower is better: untapped fixing first, lands entering tapped last.
+fn cycle_rank(land: &Card) -> usize {
+    match land_cycle(land) {
+        Some(CardIs::DualLand) => 0,
+        Some(CardIs::FetchLand) | Some(CardIs::ShockLand) => 1,
+        Some(CardIs::TricycleLand) | Some(CardIs::TriLand) => 2,
+        Some(CardIs::CheckLand)
+        | Some(CardIs::FastLand)
+        | Some(CardIs::BattleLand)
+        | Some(CardIs::ShadowLand)
+        | Some(CardIs::PainLand) => 3,
+        None => 4,
+        Some(_) => 5,
+    }
+}
+
+fn distribute_basics(
+    commander: &Card,
+    pips: &HashMap<Color, usize>,
+    basic_count: usize,
+) -> Vec<(Color, usize)> {
+    if basic_count == 0 {
+        return vec![];
+    }
+    let mut demand: Vec<(Color, usize)> = BASIC_LAND_TYPES
+        .iter()
+        .map(|(_, color)| (*color, pips.get(color).copied().unwrap_or_default()))
+        .filter(|(_, pips)| *pips > 0)
+        .collect();
+    if demand.is_empty() {
+        demand = BASIC_LAND_TYPES
+            .iter()
+            .map(|(_, color)| (*color, 1))
+            .filter(|(color, _)| commander.color_identity.contains(color))
+            .collect();
+    }
+    if demand.is_empty() {
+        return vec![(Color::Colorless, basic_count)];
+    }
+
+    // Largest remainder method, so the counts always add up to `basic_count`
+    let total_pips: usize = demand.iter().map(|(_, pips)| pips).sum();
+    let mut basics: Vec<(Color, usize, usize)> = demand
+        .iter()
+        .map(|(color, pips)| {
+            (
+                *color,
+                basic_count * pips / total_pips,
+                basic_count * pips % total_pips,
+            )
+        })
+        .collect();
+    let assigned: usize = basics.iter().map(|(_, count, _)| count).sum();
+    let mut by_remainder: Vec<usize> = (0..basics.len()).collect();
+    by_remainder.sort_by(|&i, &j| basics[j].2.cmp(&basics[i].2));
+    for i in by_remainder.into_iter().take(basic_count - assigned) {
+        basics[i].1 += 1;
+    }
+
+    basics
+        .into_iter()
+        .filter(|(_, count, _)| *count > 0)
+        .map(|(color, count, _)| (color, count))
+        .collect()
+}
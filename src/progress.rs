@@ -0,0 +1,20 @@
+/// Receives the progress of long running steps like downloading and importing the oracle cards,
+/// e.g. to show progress bars. All methods do nothing by default.
+pub trait Progress {
+    fn start(&self, _step: &str, _unit: ProgressUnit) {}
+    /// Number of finished items or bytes of the current step, with the total if known.
+    fn update(&self, _position: u64, _total: Option<u64>) {}
+    fn finish(&self) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressUnit {
+    Items,
+    Bytes,
+}
+
+/// Progress which isn't shown anywhere.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl Progress for NoProgress {}
@@ -0,0 +1,141 @@
+use mongodb::Collection;
+use scryfall::Card;
+
+use crate::{
+    budget::{Budget, Currency},
+    commander::synergy_score,
+    exclusion::Exclusions,
+    storage::find_keyword_cards,
+};
+
+pub const DEFAULT_PURCHASE_LIMIT: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct PurchaseSuggestion {
+    pub card: Card,
+    pub synergy: usize,
+}
+
+impl PurchaseSuggestion {
+    pub fn usd(&self) -> Option<f32> {
+        Currency::Usd.price(&self.card.prices)
+    }
+
+    pub fn eur(&self) -> Option<f32> {
+        Currency::Eur.price(&self.card.prices)
+    }
+}
+
+/// Suggests cards from the oracle database which are not in the collection but play into the
+/// commander's keywords, leaving out excluded cards. If the cheapest printings or languages are
+/// used, their printings are looked up in batches of `limit` candidates, a further batch only if
+/// not enough cards fit the budget.
+pub async fn cards_to_buy(
+    cards_database: &Collection<Card>,
+    commander: &Card,
+    keywords: &[String],
+    collection: &[Card],
+    exclusions: &Exclusions,
+    budget: &Budget,
+    limit: usize,
+) -> Vec<PurchaseSuggestion> {
+    let candidates = find_keyword_cards(cards_database, commander, keywords).await;
+    let ranked = rank_candidates(commander, keywords, candidates, collection, exclusions);
+    if !budget.cheapest_printings && budget.languages.is_empty() {
+        return select_purchases(ranked, budget, limit);
+    }
+
+    let mut budget = budget.clone();
+    let mut purchases = vec![];
+    let mut checked = 0;
+    while purchases.len() < limit && checked < ranked.len() {
+        let batch_end = (checked + limit.max(1)).min(ranked.len());
+        budget
+            .fetch_printings(
+                ranked[checked..batch_end]
+                    .iter()
+                    .map(|suggestion| &suggestion.card),
+            )
+            .await;
+        checked = batch_end;
+        purchases = select_purchases(ranked[..checked].to_vec(), &budget, limit);
+    }
+    purchases
+}
+
+/// Ranks unowned candidates which are not excluded by synergy and EDHREC rank. Cards not fitting
+/// the remaining budget are skipped.
+pub fn rank_purchases(
+    commander: &Card,
+    keywords: &[String],
+    candidates: Vec<Card>,
+    collection: &[Card],
+    exclusions: &Exclusions,
+    budget: &Budget,
+    limit: usize,
+) -> Vec<PurchaseSuggestion> {
+    select_purchases(
+        rank_candidates(commander, keywords, candidates, collection, exclusions),
+        budget,
+        limit,
+    )
+}
+
+/// The unowned candidates other than the commander which are not excluded, best synergy and
+/// EDHREC rank first.
+fn rank_candidates(
+    commander: &Card,
+    keywords: &[String],
+    candidates: Vec<Card>,
+    collection: &[Card],
+    exclusions: &Exclusions,
+) -> Vec<PurchaseSuggestion> {
+    let mut suggestions: Vec<PurchaseSuggestion> = candidates
+        .into_iter()
+        .filter(|card| card.oracle_id != commander.oracle_id)
+        .filter(|card| !exclusions.excludes(card))
+        .filter(|card| {
+            !collection
+                .iter()
+                .any(|owned_card| owned_card.oracle_id == card.oracle_id)
+        })
+        .map(|card| PurchaseSuggestion {
+            synergy: synergy_score(&card, keywords),
+            card,
+        })
+        .collect();
+    suggestions.sort_by(|suggestion1, suggestion2| {
+        suggestion2
+            .synergy
+            .cmp(&suggestion1.synergy)
+            .then_with(|| {
+                let rank = |suggestion: &PurchaseSuggestion| {
+                    suggestion.card.edhrec_rank.unwrap_or(usize::MAX)
+                };
+                rank(suggestion1).cmp(&rank(suggestion2))
+            })
+            .then_with(|| suggestion1.card.name.cmp(&suggestion2.card.name))
+    });
+    suggestions
+}
+
+/// Takes the ranked suggestions in their printing for the budget as long as they fit it.
+fn select_purchases(
+    suggestions: Vec<PurchaseSuggestion>,
+    budget: &Budget,
+    limit: usize,
+) -> Vec<PurchaseSuggestion> {
+    let mut spent = 0.0;
+    suggestions
+        .into_iter()
+        .filter_map(|suggestion| {
+            let card = budget.printing(&suggestion.card);
+            let price = budget.price(&card);
+            budget.allows(price, spent).then(|| {
+                spent += price.unwrap_or_default();
+                PurchaseSuggestion { card, ..suggestion }
+            })
+        })
+        .take(limit)
+        .collect()
+}
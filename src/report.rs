@@ -0,0 +1,307 @@
+use std::{collections::HashMap, fmt::Write};
+
+use scryfall::Card;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    card_utils::{extract_image_uri, extract_mana_cost, owned_copies},
+    commander::synergy_score,
+    statistics::{card_statistics, CardStatistics},
+};
+
+/// A suggested commander with its themes in a serialisable form.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommanderReport {
+    pub commander: CardReport,
+    /// Number of matched cards over all themes
+    pub score: usize,
+    pub themes: Vec<ThemeReport>,
+    pub statistics: CardStatistics,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThemeReport {
+    pub keyword: String,
+    pub cards: Vec<CardReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CardReport {
+    pub name: String,
+    pub id: Uuid,
+    pub oracle_id: Uuid,
+    /// Number of the commander's keywords the card matches
+    pub synergy: usize,
+    pub type_line: String,
+    pub mana_cost: String,
+    /// Number of copies in the collection
+    pub owned: usize,
+    pub scryfall_uri: String,
+    /// Stored image of the card in normal size
+    pub image_uri: Option<String>,
+}
+
+impl CardReport {
+    fn new(card: &Card, keywords: &[String], collection: &[(Card, usize)]) -> Self {
+        CardReport {
+            name: card.name.to_owned(),
+            id: card.id,
+            oracle_id: card.oracle_id,
+            synergy: synergy_score(card, keywords),
+            type_line: card.type_line.to_owned(),
+            mana_cost: extract_mana_cost(card),
+            owned: owned_copies(card, collection),
+            scryfall_uri: card.scryfall_uri.to_string(),
+            image_uri: extract_image_uri(card, "normal"),
+        }
+    }
+}
+
+/// Converts the result of `suggest_commanders` into reports, themes sorted by keyword and cards
+/// by synergy.
+pub fn commander_reports(
+    commander_compatible_cards: &[(Card, HashMap<String, Vec<Card>>)],
+    collection: &[(Card, usize)],
+) -> Vec<CommanderReport> {
+    commander_compatible_cards
+        .iter()
+        .map(|(commander, compatible_cards)| {
+            let mut keywords: Vec<String> = compatible_cards.keys().cloned().collect();
+            keywords.sort();
+            let themes = keywords
+                .iter()
+                .map(|keyword| {
+                    let mut cards: Vec<CardReport> = compatible_cards[keyword]
+                        .iter()
+                        .map(|card| CardReport::new(card, &keywords, collection))
+                        .collect();
+                    cards.sort_by(|card1, card2| {
+                        card2
+                            .synergy
+                            .cmp(&card1.synergy)
+                            .then_with(|| card1.name.cmp(&card2.name))
+                    });
+                    ThemeReport {
+                        keyword: keyword.to_owned(),
+                        cards,
+                    }
+                })
+                .collect();
+            CommanderReport {
+                commander: CardReport::new(commander, &keywords, collection),
+                score: compatible_cards.values().flatten().count(),
+                themes,
+                statistics: card_statistics(compatible_cards.values().flatten()),
+            }
+        })
+        .collect()
+}
+
+const HTML_STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+section { display: flex; gap: 2em; margin-bottom: 3em; }
+section > img { width: 244px; height: 340px; border-radius: 12px; }
+summary { cursor: pointer; font-weight: bold; }
+li { position: relative; }
+li img { display: none; position: absolute; left: 100%; top: 0; z-index: 1; width: 244px; }
+li:hover img { display: block; }
+th { text-align: left; padding-right: 1em; }
+";
+
+/// Renders the reports as a single HTML page. Images are linked from their stored Scryfall URLs
+/// and loaded by the browser, nothing is fetched while rendering.
+pub fn html_report(reports: &[CommanderReport]) -> String {
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Commander suggestions</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n\
+         <h1>Commander suggestions</h1>"
+    )
+    .expect("Writing HTML header");
+    for report in reports {
+        write_html_commander(&mut html, report).expect("Writing HTML commander");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn write_html_commander(html: &mut String, report: &CommanderReport) -> std::fmt::Result {
+    let commander = &report.commander;
+    writeln!(html, "<section>")?;
+    if let Some(image_uri) = &commander.image_uri {
+        writeln!(
+            html,
+            "<img src=\"{}\" alt=\"{}\" loading=\"lazy\">",
+            escape_html(image_uri),
+            escape_html(&commander.name)
+        )?;
+    }
+    writeln!(
+        html,
+        "<div>\n<h2><a href=\"{}\">{}</a></h2>\n<p>{} matched cards</p>",
+        escape_html(&commander.scryfall_uri),
+        escape_html(&commander.name),
+        report.score
+    )?;
+
+    let statistics = &report.statistics;
+    let join = |values: Vec<String>| values.join(", ");
+    let mut color_pips: Vec<String> = statistics
+        .color_pips
+        .iter()
+        .map(|(color, count)| format!("{color}: {count}"))
+        .collect();
+    color_pips.sort();
+    writeln!(html, "<table>")?;
+    for (name, value) in [
+        (
+            "Mana curve",
+            join(
+                statistics
+                    .mana_curve
+                    .iter()
+                    .map(|(mana_value, count)| format!("{mana_value}: {count}"))
+                    .collect(),
+            ),
+        ),
+        (
+            "Average mana value",
+            format!("{:.2}", statistics.average_mana_value),
+        ),
+        ("Creatures", statistics.creature_count.to_string()),
+        ("Lands", statistics.land_count.to_string()),
+        ("Colour pips", join(color_pips)),
+        (
+            "Card types",
+            join(
+                statistics
+                    .card_types
+                    .iter()
+                    .map(|(card_type, count)| format!("{card_type}: {count}"))
+                    .collect(),
+            ),
+        ),
+    ] {
+        writeln!(
+            html,
+            "<tr><th>{name}</th><td>{}</td></tr>",
+            escape_html(&value)
+        )?;
+    }
+    writeln!(html, "</table>")?;
+
+    for theme in &report.themes {
+        writeln!(
+            html,
+            "<details>\n<summary>{} ({})</summary>\n<ul>",
+            escape_html(&theme.keyword),
+            theme.cards.len()
+        )?;
+        for card in &theme.cards {
+            write!(
+                html,
+                "<li><a href=\"{}\">{}</a> (synergy {})",
+                escape_html(&card.scryfall_uri),
+                escape_html(&card.name),
+                card.synergy
+            )?;
+            if let Some(image_uri) = &card.image_uri {
+                write!(
+                    html,
+                    "<img src=\"{}\" alt=\"{}\" loading=\"lazy\">",
+                    escape_html(image_uri),
+                    escape_html(&card.name)
+                )?;
+            }
+            writeln!(html, "</li>")?;
+        }
+        writeln!(html, "</ul>\n</details>")?;
+    }
+    writeln!(html, "</div>\n</section>")
+}
+
+/// Renders the reports as Markdown with a heading and a table of all theme cards per commander.
+pub fn markdown_report(reports: &[CommanderReport]) -> String {
+    let mut markdown = String::from("# Commander suggestions\n");
+    for report in reports {
+        writeln!(
+            markdown,
+            "\n## [{}]({})\n\n{} matched cards\n\n\
+             | Theme | Card | Type line | Mana cost | Owned |\n\
+             | --- | --- | --- | --- | ---: |",
+            escape_markdown(&report.commander.name),
+            report.commander.scryfall_uri,
+            report.score
+        )
+        .expect("Writing Markdown commander");
+        for theme in &report.themes {
+            for card in &theme.cards {
+                writeln!(
+                    markdown,
+                    "| {} | [{}]({}) | {} | {} | {} |",
+                    escape_markdown(&theme.keyword),
+                    escape_markdown(&card.name),
+                    card.scryfall_uri,
+                    escape_markdown(&card.type_line),
+                    escape_markdown(&card.mana_cost),
+                    card.owned
+                )
+                .expect("Writing Markdown card");
+            }
+        }
+    }
+    markdown
+}
+
+#[derive(Serialize)]
+struct CsvReportRow<'a> {
+    commander: &'a str,
+    commander_id: Uuid,
+    theme: &'a str,
+    card: &'a str,
+    card_id: Uuid,
+    oracle_id: Uuid,
+    type_line: &'a str,
+    mana_cost: &'a str,
+    synergy: usize,
+    owned: usize,
+}
+
+/// Renders the reports as a flat CSV with one row per commander, theme and card.
+pub fn csv_report(reports: &[CommanderReport]) -> String {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for report in reports {
+        for theme in &report.themes {
+            for card in &theme.cards {
+                writer
+                    .serialize(CsvReportRow {
+                        commander: &report.commander.name,
+                        commander_id: report.commander.id,
+                        theme: &theme.keyword,
+                        card: &card.name,
+                        card_id: card.id,
+                        oracle_id: card.oracle_id,
+                        type_line: &card.type_line,
+                        mana_cost: &card.mana_cost,
+                        synergy: card.synergy,
+                        owned: card.owned,
+                    })
+                    .expect("CSV serialize report row");
+            }
+        }
+    }
+    String::from_utf8(writer.into_inner().expect("CSV writer flush")).expect("CSV UTF-8")
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,68 @@
+use std::collections::{BTreeMap, HashMap};
+
+use scryfall::{card::Color, Card};
+use serde::Serialize;
+
+use crate::{card_utils::is_land, mana_base::color_pips};
+
+const CARD_TYPES: [&str; 9] = [
+    "Artifact",
+    "Battle",
+    "Creature",
+    "Enchantment",
+    "Instant",
+    "Kindred",
+    "Land",
+    "Planeswalker",
+    "Sorcery",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CardStatistics {
+    pub card_count: usize,
+    /// Number of nonland cards per mana value
+    pub mana_curve: BTreeMap<usize, usize>,
+    /// Average mana value of the nonland cards
+    pub average_mana_value: f32,
+    pub color_pips: HashMap<Color, usize>,
+    pub card_types: BTreeMap<String, usize>,
+    pub creature_count: usize,
+    pub land_count: usize,
+}
+
+pub fn card_statistics<'a>(cards: impl IntoIterator<Item = &'a Card>) -> CardStatistics {
+    let cards: Vec<&Card> = cards.into_iter().collect();
+    let mut statistics = CardStatistics {
+        card_count: cards.len(),
+        color_pips: color_pips(cards.iter().copied()),
+        ..Default::default()
+    };
+
+    let mut total_mana_value = 0.0;
+    for card in cards {
+        for card_type in CARD_TYPES
+            .iter()
+            .filter(|card_type| card.type_line.contains(*card_type))
+        {
+            *statistics
+                .card_types
+                .entry(card_type.to_string())
+                .or_insert(0) += 1;
+        }
+        if card.type_line.contains("Creature") {
+            statistics.creature_count += 1;
+        }
+        if is_land(card) {
+            statistics.land_count += 1;
+        } else {
+            *statistics.mana_curve.entry(card.cmc as usize).or_insert(0) += 1;
+            total_mana_value += card.cmc;
+        }
+    }
+
+    let spell_count = statistics.card_count - statistics.land_count;
+    if spell_count > 0 {
+        statistics.average_mana_value = total_mana_value / spell_count as f32;
+    }
+    statistics
+}
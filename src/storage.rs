@@ -1,62 +1,567 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
-use mongodb::{bson::doc, options::ClientOptions, Client, Collection};
-use scryfall::{bulk::oracle_cards, Card};
-use serde::Deserialize;
+use mongodb::{
+    bson::{doc, Document},
+    options::ClientOptions,
+    Client, Collection, Database,
+};
+use scryfall::{
+    bulk::{cache::BulkCache, BulkDataFile},
+    set::SetCode,
+    Card,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::progress::{NoProgress, Progress, ProgressUnit};
+
+pub const DEFAULT_DATABASE_URI: &str = "mongodb://localhost:27017";
+/// Oracle cards inserted into the database at once
+const INSERT_CHUNK_SIZE: usize = 1000;
+
+/// A card of the imported collection with the number of owned copies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OwnedCard {
+    /// The oracle card, whose set, prices and images belong to Scryfall's default printing
+    pub card: Card,
+    pub count: usize,
+    /// The first owned printing if the collection CSV names one
+    #[serde(default)]
+    pub printing: Option<Printing>,
+}
+
+/// Set code and collector number of an owned printing of a card.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Printing {
+    pub set: SetCode,
+    pub collector_number: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StoreStatus {
+    pub oracle_cards: u64,
+    pub collection_cards: u64,
+    pub owned_copies: usize,
+}
 
 pub async fn update_oracle() -> Collection<Card> {
     let collection = get_card_collection().await;
+    fill_oracle(&collection, &NoProgress).await;
+    collection
+}
 
-    // Fetch and insert oracle cards if database is not populated
-    if collection
+/// Fetches and inserts the oracle cards if the database is not populated. The bulk file is
+/// downloaded to the bulk cache first unless this version is already there, and older versions
+/// are evicted from it.
+pub async fn fill_oracle(cards: &Collection<Card>, progress: &dyn Progress) {
+    if cards
         .estimated_document_count(None)
         .await
         .expect("Mongodb estimated count")
-        < 1
+        >= 1
     {
-        let cards = oracle_cards().expect("Fetch oracle cards");
-        for card in cards {
-            collection
-                .insert_one(card.expect("Fetch card"), None)
-                .await
-                .expect("Insert card");
+        return;
+    }
+    let bulk_file = BulkDataFile::<Card>::of_type_async("oracle_cards")
+        .await
+        .expect("Fetch oracle bulk file");
+    let cache_path = bulk_file.cache_path();
+    if !cache_path.exists() {
+        info!("Downloading {} to {}", bulk_file.name, cache_path.display());
+        progress.start("Downloading oracle cards", ProgressUnit::Bytes);
+        bulk_file
+            .download_async_with_progress(&cache_path, |written, total| {
+                progress.update(written, total)
+            })
+            .await
+            .expect("Download oracle cards");
+        progress.finish();
+        if let Err(error) = BulkCache::current().evict() {
+            warn!("Evicting old bulk files: {error}");
         }
     }
+
+    info!("Importing oracle cards");
+    progress.start("Importing oracle cards", ProgressUnit::Items);
+    // The cached file is parsed on a blocking thread while the parsed chunks are inserted
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(2);
+    let parsing = tokio::task::spawn_blocking(move || {
+        let oracle_cards = bulk_file.load_iter().expect("Load oracle cards");
+        let mut chunk = Vec::with_capacity(INSERT_CHUNK_SIZE);
+        for card in oracle_cards {
+            chunk.push(card.expect("Read card"));
+            if chunk.len() == INSERT_CHUNK_SIZE {
+                let full_chunk =
+                    std::mem::replace(&mut chunk, Vec::with_capacity(INSERT_CHUNK_SIZE));
+                if sender.blocking_send(full_chunk).is_err() {
+                    return;
+                }
+            }
+        }
+        if !chunk.is_empty() {
+            // A closed channel means the inserts failed, which is reported there
+            let _ = sender.blocking_send(chunk);
+        }
+    });
+    let mut inserted = 0;
+    while let Some(chunk) = receiver.recv().await {
+        cards.insert_many(&chunk, None).await.expect("Insert cards");
+        inserted += chunk.len();
+        progress.update(inserted as u64, None);
+    }
+    parsing.await.expect("Parse oracle cards");
+    progress.finish();
+}
+
+/// Replaces the stored oracle cards with the current Scryfall bulk data. The cards are imported
+/// into a separate collection which then takes the place of the stored one, so the stored cards
+/// stay available if the download or import fails.
+pub async fn refresh_oracle(database: &Database, progress: &dyn Progress) {
+    let refreshed_cards = database.collection::<Card>("cards_refresh");
+    clear_oracle(&refreshed_cards).await;
+    fill_oracle(&refreshed_cards, progress).await;
+    refreshed_cards
+        .aggregate([doc! {"$out": card_collection(database).name()}], None)
+        .await
+        .expect("Mongo replace cards");
+    clear_oracle(&refreshed_cards).await;
+}
+
+pub async fn clear_oracle(cards: &Collection<Card>) {
+    cards.drop(None).await.expect("Mongo drop cards");
+}
+
+pub async fn store_status(database: &Database) -> StoreStatus {
+    let collection = load_collection(&owned_card_collection(database)).await;
+    StoreStatus {
+        oracle_cards: card_collection(database)
+            .count_documents(None, None)
+            .await
+            .expect("Mongo count cards"),
+        collection_cards: collection.len() as u64,
+        owned_copies: collection.iter().map(|(_, count)| count).sum(),
+    }
+}
+
+/// Replaces the stored collection.
+pub async fn save_collection(owned_cards: &Collection<OwnedCard>, collection: &[OwnedCard]) {
+    owned_cards.drop(None).await.expect("Mongo drop collection");
+    if collection.is_empty() {
+        return;
+    }
+    owned_cards
+        .insert_many(collection, None)
+        .await
+        .expect("Mongo insert collection");
+}
+
+/// Reads the stored collection with the number of owned copies of every card, sorted by name.
+pub async fn load_collection(owned_cards: &Collection<OwnedCard>) -> Vec<(Card, usize)> {
+    load_owned_cards(owned_cards)
+        .await
+        .into_iter()
+        .map(|owned_card| (owned_card.card, owned_card.count))
+        .collect()
+}
+
+/// Reads the stored collection including the owned printings, sorted by name.
+pub async fn load_owned_cards(owned_cards: &Collection<OwnedCard>) -> Vec<OwnedCard> {
+    let mut cursor = owned_cards
+        .find(None, None)
+        .await
+        .expect("Mongo find collection");
+    let mut collection = vec![];
+    while cursor.advance().await.expect("Mongo cursor advance") {
+        collection.push(
+            cursor
+                .deserialize_current()
+                .expect("Mongo deserialize owned card"),
+        );
+    }
+    collection.sort_by(|owned_card1: &OwnedCard, owned_card2| {
+        owned_card1.card.name.cmp(&owned_card2.card.name)
+    });
     collection
 }
 
+/// The owned printings of the collection by oracle id.
+pub fn owned_printings(collection: &[OwnedCard]) -> HashMap<Uuid, Printing> {
+    collection
+        .iter()
+        .filter_map(|owned_card| {
+            owned_card
+                .printing
+                .clone()
+                .map(|printing| (owned_card.card.oracle_id, printing))
+        })
+        .collect()
+}
+
 pub async fn read_deckbox_collection(cards: Collection<Card>, csv_path: PathBuf) -> Vec<Card> {
+    read_deckbox_collection_quantities(cards, csv_path, &NoProgress)
+        .await
+        .into_iter()
+        .map(|owned_card| owned_card.card)
+        .collect()
+}
+
+/// Reads the collection together with the number of owned copies of every card. Rows of the
+/// same card, e.g. different printings, are added up. If the CSV has set code and collector
+/// number columns, the first owned printing is kept next to the oracle card.
+pub async fn read_deckbox_collection_quantities(
+    cards: Collection<Card>,
+    csv_path: PathBuf,
+    progress: &dyn Progress,
+) -> Vec<OwnedCard> {
     let mut rdr = csv::Reader::from_path(csv_path).expect("CSV Reader path");
-    let mut recognised_cards = vec![];
-    for line in rdr.deserialize() {
-        let csv_card: CsvCard = line.expect("CSV line");
+    let csv_cards: Vec<CsvCard> = rdr
+        .deserialize()
+        .map(|line| line.expect("CSV line"))
+        .collect();
+    progress.start("Matching collection", ProgressUnit::Items);
+    let mut recognised_cards: Vec<OwnedCard> = vec![];
+    for (matched, csv_card) in csv_cards.iter().enumerate() {
+        progress.update(matched as u64, Some(csv_cards.len() as u64));
         match cards
             .find_one(doc! {"name": &csv_card.name}, None)
             .await
             .expect("Mongo find card")
         {
-            Some(card) => {
-                if !recognised_cards.contains(&card) {
-                    recognised_cards.push(card)
-                }
-            }
-            None => println!("{} not found!", &csv_card.name),
+            Some(card) => match recognised_cards
+                .iter_mut()
+                .find(|owned_card| owned_card.card.oracle_id == card.oracle_id)
+            {
+                Some(owned_card) => owned_card.count += csv_card.count,
+                None => recognised_cards.push(OwnedCard {
+                    card,
+                    count: csv_card.count,
+                    printing: csv_card.printing(),
+                }),
+            },
+            None => warn!("{} not found!", &csv_card.name),
         }
     }
+    progress.update(csv_cards.len() as u64, Some(csv_cards.len() as u64));
+    progress.finish();
     recognised_cards
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct Decklist {
+    pub commander: Option<Card>,
+    /// The companion of a "Companion" section, which isn't part of the deck
+    pub companion: Option<Card>,
+    pub cards: Vec<Card>,
+}
+
+/// The section of a decklist a line belongs to, sideboard lines are left out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DecklistSection {
+    Commander,
+    Companion,
+    Deck,
+}
+
+/// Reads a decklist, either a CSV file with 'Name' and 'Count' columns or a text file with one
+/// card per line like "1 Sol Ring" or "1x Sol Ring (C21) 263". The commander is taken from a
+/// "Commander" section or a "[Commander]" tag, otherwise it is the first legendary creature.
+pub async fn read_decklist(cards: Collection<Card>, path: PathBuf) -> Decklist {
+    let entries: Vec<(String, usize, DecklistSection)> =
+        if path.extension().map_or(false, |ext| ext == "csv") {
+            csv::Reader::from_path(path)
+                .expect("CSV Reader path")
+                .deserialize()
+                .map(|line| {
+                    let csv_card: CsvCard = line.expect("CSV line");
+                    (csv_card.name, csv_card.count, DecklistSection::Deck)
+                })
+                .collect()
+        } else {
+            parse_decklist(&std::fs::read_to_string(path).expect("Read decklist"))
+        };
+
+    let mut decklist = Decklist::default();
+    for (name, quantity, section) in entries {
+        match find_card_by_name(&cards, &name).await {
+            Some(card) if section == DecklistSection::Commander && decklist.commander.is_none() => {
+                decklist.commander = Some(card)
+            }
+            Some(card) if section == DecklistSection::Companion => decklist.companion = Some(card),
+            Some(card) => decklist
+                .cards
+                .extend(std::iter::repeat(card).take(quantity)),
+            None => warn!("{} not found!", &name),
+        }
+    }
+    if decklist.commander.is_none() {
+        if let Some(position) = decklist.cards.iter().position(|card| {
+            card.type_line.contains("Legendary") && card.type_line.contains("Creature")
+        }) {
+            decklist.commander = Some(decklist.cards.remove(position));
+        }
+    }
+    decklist
+}
+
+/// Splits a text decklist into card names with their quantity and section. A "[Commander]" tag
+/// moves a line of any section into the commander section.
+fn parse_decklist(text: &str) -> Vec<(String, usize, DecklistSection)> {
+    let mut section = DecklistSection::Deck;
+    let mut in_sideboard = false;
+    let mut entries = vec![];
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let heading = line
+            .trim_start_matches("//")
+            .trim_end_matches(':')
+            .trim()
+            .to_lowercase();
+        match heading.as_str() {
+            "commander" => {
+                section = DecklistSection::Commander;
+                in_sideboard = false;
+                continue;
+            }
+            "companion" => {
+                section = DecklistSection::Companion;
+                in_sideboard = false;
+                continue;
+            }
+            "deck" | "main" | "mainboard" => {
+                section = DecklistSection::Deck;
+                in_sideboard = false;
+                continue;
+            }
+            "sideboard" | "maybeboard" | "considering" => {
+                in_sideboard = true;
+                continue;
+            }
+            _ if line.starts_with("//") || line.starts_with('#') => continue,
+            _ => {}
+        }
+        if in_sideboard {
+            continue;
+        }
+
+        let (quantity, mut name) = match line.split_once(' ') {
+            Some((count, name)) if is_quantity(count) => {
+                (count.trim_end_matches('x').parse().unwrap_or(1), name)
+            }
+            _ => (1, line),
+        };
+        let line_section = if line.contains("[Commander") {
+            DecklistSection::Commander
+        } else {
+            section
+        };
+        // Cut off set code and collector number, tags and foil markers
+        for suffix in [" (", " [", " *"] {
+            if let Some(position) = name.find(suffix) {
+                name = &name[..position];
+            }
+        }
+        entries.push((name.trim().to_owned(), quantity, line_section));
+    }
+    entries
+}
+
+/// Whether the first word of a decklist line is a quantity like "4" or "1x".
+fn is_quantity(word: &str) -> bool {
+    let digits = word.strip_suffix('x').unwrap_or(word);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+pub async fn find_card_by_name(cards: &Collection<Card>, name: &str) -> Option<Card> {
+    match cards
+        .find_one(doc! {"name": name}, None)
+        .await
+        .expect("Mongo find card")
+    {
+        Some(card) => Some(card),
+        // Decklists usually only name the front face of multiface cards
+        None => cards
+            .find_one(doc! {"card_faces.0.name": name}, None)
+            .await
+            .expect("Mongo find card"),
+    }
+}
+
+/// Finds all cards within the commander's colour identity whose type line or oracle text
+/// contains one of the keywords.
+pub async fn find_keyword_cards(
+    cards: &Collection<Card>,
+    commander: &Card,
+    keywords: &[String],
+) -> Vec<Card> {
+    if keywords.is_empty() {
+        return vec![];
+    }
+    let color_identity: Vec<String> = commander
+        .color_identity
+        .iter()
+        .map(|color| color.to_string())
+        .collect();
+    let keyword_filters: Vec<Document> = keywords
+        .iter()
+        .flat_map(|keyword| {
+            let pattern = escape_regex(keyword);
+            [
+                doc! {"type_line": {"$regex": &pattern}},
+                doc! {"oracle_text": {"$regex": &pattern}},
+                doc! {"card_faces.oracle_text": {"$regex": &pattern}},
+            ]
+        })
+        .collect();
+    let filter = doc! {
+        "color_identity": {"$not": {"$elemMatch": {"$nin": color_identity}}},
+        "legalities.commander": "legal",
+        "$or": keyword_filters,
+    };
+
+    let mut cursor = cards.find(filter, None).await.expect("Mongo find cards");
+    let mut found_cards = vec![];
+    while cursor.advance().await.expect("Mongo cursor advance") {
+        found_cards.push(
+            cursor
+                .deserialize_current()
+                .expect("Mongo deserialize card"),
+        );
+    }
+    found_cards
+}
+
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$/".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 pub async fn get_card_collection() -> Collection<Card> {
-    let client_options = ClientOptions::parse("mongodb://localhost:27017")
+    card_collection(&connect(DEFAULT_DATABASE_URI).await)
+}
+
+pub async fn connect(uri: &str) -> Database {
+    let client_options = ClientOptions::parse(uri)
         .await
         .expect("Mongo parse client options");
     let client = Client::with_options(client_options).expect("Mongo create client");
-    let db = client.database("oracle_cards");
-    db.collection::<Card>("cards")
+    client.database("oracle_cards")
+}
+
+pub fn card_collection(database: &Database) -> Collection<Card> {
+    database.collection::<Card>("cards")
+}
+
+pub fn owned_card_collection(database: &Database) -> Collection<OwnedCard> {
+    database.collection::<OwnedCard>("collection")
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 struct CsvCard {
     name: String,
+    #[serde(default = "single_copy")]
+    count: usize,
+    /// Set code of the owned printing
+    #[serde(default, alias = "Set Code", alias = "Edition Code")]
+    set: Option<String>,
+    #[serde(default, rename = "Card Number", alias = "Collector Number")]
+    card_number: Option<String>,
+}
+
+impl CsvCard {
+    /// The printing named by the set code and collector number columns.
+    fn printing(&self) -> Option<Printing> {
+        match (&self.set, &self.card_number) {
+            (Some(set), Some(collector_number)) => Some(Printing {
+                set: SetCode::new(&set.to_lowercase()).ok()?,
+                collector_number: collector_number.to_owned(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+fn single_copy() -> usize {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        name: &str,
+        quantity: usize,
+        section: DecklistSection,
+    ) -> (String, usize, DecklistSection) {
+        (name.to_owned(), quantity, section)
+    }
+
+    #[test]
+    fn parse_sections_and_quantities() {
+        let text = "
+            Commander
+            1 Abomination of Llanowar
+
+            Companion
+            1 Lurrus of the Dream-Den
+
+            Deck
+            1x Sol Ring (C21) 263
+            12 Forest
+            Llanowar Elves *F*
+
+            Sideboard
+            1 Swords to Plowshares
+        ";
+        assert_eq!(
+            parse_decklist(text),
+            [
+                entry("Abomination of Llanowar", 1, DecklistSection::Commander),
+                entry("Lurrus of the Dream-Den", 1, DecklistSection::Companion),
+                entry("Sol Ring", 1, DecklistSection::Deck),
+                entry("Forest", 12, DecklistSection::Deck),
+                entry("Llanowar Elves", 1, DecklistSection::Deck),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_tags_comments_and_headings() {
+        let text = "
+            // Elves
+            # Ramp first
+            1 Elvish Archdruid
+            1 Abomination of Llanowar (DMU) 189 [Commander{top}]
+            //Maybeboard:
+            1 Elvish Mystic
+            MAIN:
+            1 Llanowar Elves
+        ";
+        assert_eq!(
+            parse_decklist(text),
+            [
+                entry("Elvish Archdruid", 1, DecklistSection::Deck),
+                entry("Abomination of Llanowar", 1, DecklistSection::Commander),
+                entry("Llanowar Elves", 1, DecklistSection::Deck),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_names_starting_with_numbers() {
+        assert_eq!(
+            parse_decklist("1 1996 World Champion\n+2 Mace\n"),
+            [
+                entry("1996 World Champion", 1, DecklistSection::Deck),
+                entry("+2 Mace", 1, DecklistSection::Deck),
+            ]
+        );
+    }
 }
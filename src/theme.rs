@@ -0,0 +1,122 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use scryfall::{search::query::Query, Card};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    card_utils::extract_card_colors,
+    exclusion::{parse_local_query, Exclusions},
+    sort_by_card_count,
+};
+
+/// A theme of its own next to the catalogued keywords, e.g. tokens or graveyard recursion. The
+/// cards matching `cards` are grouped under `name` for the commanders matching `commander`, or
+/// for every commander if it's not given.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeRule {
+    pub name: String,
+    pub commander: Option<Query>,
+    pub cards: Query,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    #[serde(default)]
+    themes: Vec<ThemeEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeEntry {
+    name: String,
+    commander: Option<String>,
+    cards: String,
+}
+
+impl ThemeRule {
+    pub fn applies_to(&self, commander: &Card) -> bool {
+        self.commander
+            .as_ref()
+            .map_or(true, |query| query.matches(commander).unwrap_or(false))
+    }
+
+    pub fn matches(&self, card: &Card) -> bool {
+        self.cards.matches(card).unwrap_or(false)
+    }
+}
+
+pub fn read_theme_rules(path: &Path) -> Result<Vec<ThemeRule>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| format!("Reading {}: {error}", path.display()))?;
+    parse_theme_rules(&text).map_err(|error| format!("Parsing {}: {error}", path.display()))
+}
+
+/// Parses the `[[themes]]` tables of a theme rule file, each with a `name`, a Scryfall search of
+/// the `cards` and optionally one of the `commander`. Searches have to be evaluable locally.
+pub fn parse_theme_rules(text: &str) -> Result<Vec<ThemeRule>, String> {
+    let file: ThemeFile = toml::from_str(text).map_err(|error| error.to_string())?;
+    let parse = |query: &str| {
+        parse_local_query(query).map_err(|error| format!("Invalid search {query}: {error}"))
+    };
+    file.themes
+        .into_iter()
+        .map(|entry| {
+            Ok(ThemeRule {
+                commander: entry.commander.as_deref().map(parse).transpose()?,
+                cards: parse(&entry.cards)?,
+                name: entry.name,
+            })
+        })
+        .collect()
+}
+
+/// Adds a group for every theme rule applying to a suggested commander with the cards of the
+/// collection matching it. As with keywords, cards outside the commander's colours and excluded
+/// cards are left out, and so are cards already grouped for the commander and themes without
+/// cards. The suggestions are ordered by their number of cards again afterwards.
+pub fn add_themes(
+    suggestions: &mut [(Card, HashMap<String, Vec<Card>>)],
+    rules: &[ThemeRule],
+    collection: &[Card],
+    exclusions: &Exclusions,
+) {
+    if rules.is_empty() {
+        return;
+    }
+    for (commander, keyword_cards) in suggestions.iter_mut() {
+        if exclusions.excludes(commander) {
+            continue;
+        }
+        let mut grouped: HashSet<Uuid> = keyword_cards
+            .values()
+            .flatten()
+            .map(|card| card.oracle_id)
+            .collect();
+        for rule in rules.iter().filter(|rule| rule.applies_to(commander)) {
+            let cards: Vec<Card> = collection
+                .iter()
+                .filter(|card| !exclusions.excludes(card))
+                .filter(|card| {
+                    extract_card_colors(card)
+                        .iter()
+                        .all(|color| commander.color_identity.contains(color))
+                })
+                .filter(|card| rule.matches(card))
+                .filter(|card| grouped.insert(card.oracle_id))
+                .cloned()
+                .collect();
+            if !cards.is_empty() {
+                keyword_cards
+                    .entry(rule.name.to_owned())
+                    .or_insert_with(Vec::new)
+                    .extend(cards);
+            }
+        }
+    }
+    sort_by_card_count(suggestions);
+}
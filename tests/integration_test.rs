@@ -1,10 +1,38 @@
-use mtg_commander_suggestions::{commander_suggestions, storage::get_card_collection};
+use mtg_commander_suggestions::{
+    allocation::allocate_copies,
+    analysis::analyze_deck_with_keywords,
+    budget::{Budget, Currency},
+    check_selected, commander_suggestions,
+    config::parse_config,
+    deck::{build_deck, Deck},
+    exclusion::{parse_local_query, Exclusions, PowerLevel},
+    export::{
+        archidekt_text, arena_text, deck_export_cards, export_csv, export_text, ExportFormat,
+    },
+    import_collection,
+    mana_base::{color_pips, generate_mana_base, land_cycle, ManaBase},
+    progress::NoProgress,
+    purchase::rank_purchases,
+    report::{commander_reports, csv_report, html_report, markdown_report},
+    statistics::card_statistics,
+    storage::{
+        connect, get_card_collection, load_collection, owned_card_collection, Printing,
+        DEFAULT_DATABASE_URI,
+    },
+    suggest_for_commander_with_catalogs,
+    theme::{add_themes, parse_theme_rules},
+};
 use std::{
+    collections::HashMap,
     fs::File,
     path::PathBuf,
 };
 
-use scryfall::Card;
+use scryfall::{
+    card::Color,
+    search::{prelude::CardIs, query::Query},
+    Card,
+};
 
 #[tokio::test]
 async fn recognise_card() {
@@ -13,11 +41,664 @@ async fn recognise_card() {
     assert_eq!(result.len(), 1);
     let (_, keywords) = &result[0];
     assert!(keywords.keys().any(|keyword| keyword.eq("Elf")));
+
+    let database = connect(DEFAULT_DATABASE_URI).await;
+    import_collection(
+        &database,
+        PathBuf::from("./tests/minimal-collection.csv"),
+        &NoProgress,
+    )
+    .await;
+    let collection = load_collection(&owned_card_collection(&database)).await;
+    assert_eq!(collection.len(), 1);
+    assert_eq!(collection[0].1, 1);
+}
+
+#[test]
+fn suggest_for_unowned_commander() {
+    let commander = load_card();
+    let (suggested_commander, keywords) = suggest_for_commander_with_catalogs(
+        &commander,
+        &[elf()],
+        &Exclusions::default(),
+        &[vec!["Horror".to_owned()], vec!["Elf".to_owned()]],
+    );
+    assert_eq!(suggested_commander, commander);
+    assert_eq!(keywords["Elf"][0].name, "Llanowar Elves");
+}
+
+#[test]
+fn mana_base_from_pips() {
+    let commander = load_card();
+    let shock_land = land(
+        &commander,
+        "Overgrown Tomb",
+        "Land — Swamp Forest",
+        vec![Color::Black, Color::Green],
+    );
+    let off_color_land = land(
+        &commander,
+        "Sacred Foundry",
+        "Land — Mountain Plains",
+        vec![Color::Red, Color::White],
+    );
+
+    let mana_base = generate_mana_base(
+        &commander,
+        &[commander.clone()],
+        &[shock_land, off_color_land],
+        10,
+        &Budget::default(),
+        0.0,
+    );
+
+    assert_eq!(mana_base.land_count(), 10);
+    assert_eq!(mana_base.nonbasic_lands.len(), 1);
+    assert_eq!(
+        land_cycle(&mana_base.nonbasic_lands[0]),
+        Some(CardIs::ShockLand)
+    );
+    assert_eq!(
+        mana_base.basic_lands,
+        vec![(Color::Black, 5), (Color::Green, 4)]
+    );
+}
+
+#[test]
+fn statistics_of_matched_cards() {
+    let commander = load_card();
+    let forest = land(&commander, "Forest", "Basic Land — Forest", vec![Color::Green]);
+
+    let statistics = card_statistics([&commander, &forest]);
+
+    assert_eq!(statistics.card_count, 2);
+    assert_eq!(statistics.mana_curve.into_iter().collect::<Vec<_>>(), vec![(3, 1)]);
+    assert_eq!(statistics.average_mana_value, 3.0);
+    assert_eq!(statistics.creature_count, 1);
+    assert_eq!(statistics.land_count, 1);
+    assert_eq!(statistics.color_pips[&Color::Black], 1);
+    assert_eq!(statistics.color_pips[&Color::Green], 1);
+    assert_eq!(statistics.card_types["Creature"], 1);
+    assert_eq!(statistics.card_types["Land"], 1);
+}
+
+#[test]
+fn color_pips_of_hybrid_phyrexian_and_split_costs() {
+    let mut hybrid = load_card();
+    hybrid.mana_cost = Some("{2}{B/G}{G/P}".to_owned());
+    let mut split = load_card();
+    split.mana_cost = Some("{1}{W} // {2/U}{X}".to_owned());
+
+    let pips = color_pips([&hybrid, &split]);
+
+    assert_eq!(pips[&Color::Black], 1);
+    assert_eq!(pips[&Color::Green], 2);
+    assert_eq!(pips[&Color::White], 1);
+    assert_eq!(pips[&Color::Blue], 1);
+    assert_eq!(pips.get(&Color::Red), None);
+}
+
+#[test]
+fn mana_costs_of_modal_double_faced_cards() {
+    let commander = load_card();
+    let valki = load_mdfc();
+
+    let pips = color_pips([&valki]);
+    assert_eq!(pips[&Color::Black], 2);
+    assert_eq!(pips[&Color::Red], 1);
+    assert_eq!(pips.len(), 2);
+
+    let suggestions = vec![(commander, HashMap::from([("God".to_owned(), vec![valki])]))];
+    let reports = serde_json::to_value(commander_reports(&suggestions, &[])).unwrap();
+    assert_eq!(
+        reports[0]["themes"][0]["cards"][0]["mana_cost"],
+        "{1}{B} // {5}{B}{R}"
+    );
+}
+
+#[test]
+fn allocate_contested_card_by_synergy() {
+    let elf_card = load_card();
+    let mut elf_commander = elf_card.clone();
+    elf_commander.oracle_id = "00000000-0000-0000-0000-000000000001".parse().unwrap();
+    let mut horror_commander = elf_card.clone();
+    horror_commander.oracle_id = "00000000-0000-0000-0000-000000000002".parse().unwrap();
+    let deck = |commander: &Card, keywords: &[&str]| Deck {
+        commander: commander.clone(),
+        keywords: keywords.iter().map(|keyword| keyword.to_string()).collect(),
+        spells: vec![elf_card.clone()],
+        lands: ManaBase {
+            nonbasic_lands: vec![],
+            basic_lands: vec![],
+        },
+    };
+    let decks = vec![
+        deck(&horror_commander, &["Horror"]),
+        deck(&elf_commander, &["Elf", "Horror"]),
+    ];
+    let collection = vec![
+        (elf_card.clone(), 1),
+        (elf_commander.clone(), 1),
+        (horror_commander.clone(), 1),
+    ];
+
+    let allocation = allocate_copies(decks, &collection);
+
+    assert_eq!(allocation.conflicts.len(), 1);
+    let conflict = &allocation.conflicts[0];
+    assert_eq!(conflict.owned, 1);
+    assert_eq!(conflict.suggested_decks()[0].0.oracle_id, elf_commander.oracle_id);
+    assert_eq!(conflict.short_decks()[0].0.oracle_id, horror_commander.oracle_id);
+    assert!(allocation.decks[0].spells.is_empty());
+    assert_eq!(allocation.decks[1].spells.len(), 1);
+    assert_eq!(
+        allocation.decks[0].missing_cards(),
+        allocation.decks[1].missing_cards() + 1
+    );
+}
+
+#[test]
+fn selected_commanders_need_a_suggestion() {
+    let commander = load_card();
+    let suggestions = vec![(commander.clone(), HashMap::new())];
+    assert!(check_selected(&suggestions, &[]).is_ok());
+    assert!(check_selected(&suggestions, &[commander.name.clone()]).is_ok());
+    let error = check_selected(
+        &suggestions,
+        &[commander.name, "Lathril, Blade of the Elves".to_owned()],
+    )
+    .unwrap_err();
+    assert!(error.starts_with("No suggestion for Lathril, Blade of the Elves:"));
+}
+
+#[test]
+fn analyze_deck_suggests_swaps() {
+    let commander = load_card();
+    let elf = elf();
+    let mut horror = elf.clone();
+    horror.oracle_id = "00000000-0000-0000-0000-000000000002".parse().unwrap();
+    horror.name = "Nightmare Horror".to_owned();
+    horror.type_line = "Creature — Horror".to_owned();
+    let keywords = vec!["Elf".to_owned()];
+
+    let analysis = analyze_deck_with_keywords(
+        &commander,
+        &keywords,
+        &[horror.clone()],
+        &[commander.clone(), elf.clone(), horror.clone()],
+        &Exclusions::default(),
+    );
+
+    assert_eq!(analysis.missing_keyword_cards["Elf"].len(), 1);
+    assert_eq!(analysis.missing_keyword_cards["Elf"][0].name, elf.name);
+    assert_eq!(analysis.off_theme_cards.len(), 1);
+    assert_eq!(analysis.off_theme_cards[0].name, horror.name);
+    assert_eq!(analysis.swaps.len(), 1);
+    assert_eq!(analysis.swaps[0].add.name, elf.name);
+}
+
+#[test]
+fn purchases_within_budget() {
+    let commander = load_card();
+    let owned = priced_card(1, "Owned Elf", Currency::Usd, "0.50");
+    let expensive = priced_card(2, "Expensive Elf", Currency::Usd, "30.00");
+    let cheap = priced_card(3, "Cheap Elf", Currency::Usd, "1.00");
+    let banned = priced_card(4, "Banned Elf", Currency::Usd, "0.10");
+    let keywords = vec!["Elf".to_owned()];
+
+    let purchases = rank_purchases(
+        &commander,
+        &keywords,
+        vec![commander.clone(), owned.clone(), expensive, cheap, banned],
+        &[owned],
+        &Exclusions {
+            names: vec!["Banned Elf".to_owned()],
+            ..Default::default()
+        },
+        &Budget {
+            total_limit: Some(10.0),
+            ..Default::default()
+        },
+        20,
+    );
+
+    assert_eq!(purchases.len(), 1);
+    assert_eq!(purchases[0].card.name, "Cheap Elf");
+    assert_eq!(purchases[0].usd(), Some(1.0));
+}
+
+#[test]
+fn budget_prefers_owned_then_cheapest_printing() {
+    let card = load_card();
+    let printing = |set: &str, usd: &str| {
+        let mut printing = card.clone();
+        printing.set = set.try_into().unwrap();
+        printing.prices.usd = Some(usd.to_owned());
+        printing
+    };
+    let mut budget = Budget {
+        cheapest_printings: true,
+        printings: HashMap::from([(
+            card.oracle_id,
+            vec![
+                printing("khc", "2.00"),
+                printing("j22", "NaN"),
+                printing("dmc", "0.50"),
+            ],
+        )]),
+        ..Default::default()
+    };
+    assert_eq!(budget.printing(&card).set.to_string(), "dmc");
+
+    budget.owned_printings = HashMap::from([(
+        card.oracle_id,
+        Printing {
+            set: "khc".try_into().unwrap(),
+            collector_number: card.collector_number.clone(),
+        },
+    )]);
+    assert_eq!(budget.printing(&card).set.to_string(), "khc");
+
+    let mut german = printing("cmr", "3.00");
+    german.lang = "de".to_owned();
+    let printings = budget.printings.get_mut(&card.oracle_id).unwrap();
+    printings.push(german);
+    budget.languages = vec!["de".to_owned(), "en".to_owned()];
+    assert_eq!(budget.printing(&card).set.to_string(), "khc");
+    budget.owned_printings.clear();
+    assert_eq!(budget.printing(&card).set.to_string(), "cmr");
+    budget.languages = vec!["ja".to_owned()];
+    assert_eq!(budget.printing(&card).set.to_string(), "dmc");
+
+    budget.cheapest_printings = false;
+    assert_eq!(budget.printing(&card).set.to_string(), "khc");
+    budget.languages = vec!["de".to_owned()];
+    assert_eq!(budget.printing(&card).set.to_string(), "cmr");
+}
+
+#[test]
+fn deck_within_card_budget() {
+    let commander = load_card();
+    let cheap = priced_card(1, "Cheap Elf", Currency::Eur, "1.00");
+    let expensive = priced_card(2, "Expensive Elf", Currency::Eur, "30.00");
+    let keyword_cards = HashMap::from([("Elf".to_owned(), vec![cheap.clone(), expensive])]);
+    let budget = Budget {
+        currency: Currency::Eur,
+        card_limit: Some(5.0),
+        ..Default::default()
+    };
+
+    let deck = build_deck(&commander, &keyword_cards, &[cheap], 37, &budget);
+
+    assert_eq!(deck.spells.len(), 1);
+    assert_eq!(deck.spells[0].name, "Cheap Elf");
+    assert_eq!(deck.total_value(&budget), 1.05);
+}
+
+#[test]
+fn reports_serialise_themes() {
+    let commander = load_card();
+    let elf = elf();
+    let suggestions = vec![(
+        commander.clone(),
+        HashMap::from([
+            ("Horror".to_owned(), vec![commander.clone()]),
+            ("Elf".to_owned(), vec![elf, commander.clone()]),
+        ]),
+    )];
+
+    let reports = serde_json::to_value(commander_reports(&suggestions, &[])).unwrap();
+
+    let report = &reports[0];
+    assert_eq!(report["commander"]["name"], commander.name.as_str());
+    assert_eq!(report["commander"]["id"], commander.id.to_string());
+    assert_eq!(report["score"], 3);
+    assert_eq!(report["themes"][0]["keyword"], "Elf");
+    assert_eq!(report["themes"][0]["cards"][0]["name"], commander.name.as_str());
+    assert_eq!(report["themes"][0]["cards"][0]["synergy"], 2);
+    assert_eq!(report["themes"][0]["cards"][1]["name"], "Llanowar Elves");
+    assert_eq!(report["themes"][0]["cards"][1]["synergy"], 1);
+    assert_eq!(report["themes"][1]["keyword"], "Horror");
+    assert_eq!(report["statistics"]["card_count"], 3);
+}
+
+#[test]
+fn html_report_links_stored_images() {
+    let commander = load_card();
+    let suggestions = vec![(
+        commander.clone(),
+        HashMap::from([("Elf".to_owned(), vec![commander.clone()])]),
+    )];
+
+    let html = html_report(&commander_reports(&suggestions, &[]));
+
+    assert!(html.contains(commander.image_uris["normal"].as_str()));
+    assert!(html.contains(&format!("href=\"{}\"", commander.scryfall_uri)));
+    assert!(html.contains("<summary>Elf (1)</summary>"));
+    assert!(html.contains("<th>Creatures</th><td>1</td>"));
+}
+
+#[test]
+fn export_deck_for_import() {
+    let commander = load_card();
+    let mut elf = elf();
+    elf.set = "m19".try_into().unwrap();
+    elf.collector_number = "314".to_owned();
+    let deck = Deck {
+        commander,
+        keywords: vec!["Elf".to_owned()],
+        spells: vec![elf],
+        lands: ManaBase {
+            nonbasic_lands: vec![],
+            basic_lands: vec![(Color::Black, 0), (Color::Green, 36)],
+        },
+    };
+    let export_cards = deck_export_cards(&deck, &HashMap::new());
+
+    assert_eq!(
+        arena_text(&export_cards),
+        "Commander\n1 Abomination of Llanowar (KHC) 81\n\nDeck\n1 Llanowar Elves (M19) 314\n36 Forest\n"
+    );
+    assert_eq!(
+        archidekt_text(&export_cards),
+        "1x Abomination of Llanowar (KHC) 81 [Commander]\n1x Llanowar Elves (M19) 314\n36x Forest\n"
+    );
+    assert_eq!(
+        export_csv(&export_cards),
+        "Count,Name,Edition,Collector Number,Category\n\
+         1,Abomination of Llanowar,khc,81,Commander\n\
+         1,Llanowar Elves,m19,314,Mainboard\n\
+         36,Forest,,,Mainboard\n"
+    );
+
+    let owned_printings = HashMap::from([(
+        deck.commander.oracle_id,
+        Printing {
+            set: "dmu".try_into().unwrap(),
+            collector_number: "189".to_owned(),
+        },
+    )]);
+    assert_eq!(
+        arena_text(&deck_export_cards(&deck, &owned_printings)),
+        "Commander\n1 Abomination of Llanowar (DMU) 189\n\nDeck\n1 Llanowar Elves (M19) 314\n36 Forest\n"
+    );
+    let two_decks = [export_cards.clone(), export_cards];
+    assert_eq!(
+        export_text(&two_decks, ExportFormat::Csv),
+        "Deck,Count,Name,Edition,Collector Number,Category\n\
+         Abomination of Llanowar,1,Abomination of Llanowar,khc,81,Commander\n\
+         Abomination of Llanowar,1,Llanowar Elves,m19,314,Mainboard\n\
+         Abomination of Llanowar,36,Forest,,,Mainboard\n\
+         Abomination of Llanowar,1,Abomination of Llanowar,khc,81,Commander\n\
+         Abomination of Llanowar,1,Llanowar Elves,m19,314,Mainboard\n\
+         Abomination of Llanowar,36,Forest,,,Mainboard\n"
+    );
+    let arena = export_text(&two_decks, ExportFormat::Arena);
+    let deck_start = "About\nName Abomination of Llanowar\n\nCommander\n";
+    assert_eq!(arena.matches(deck_start).count(), 2);
+    assert_eq!(
+        export_text(&two_decks[..1], ExportFormat::Arena),
+        arena_text(&two_decks[0])
+    );
+}
+
+#[test]
+fn markdown_and_csv_reports_list_owned_cards() {
+    let commander = load_card();
+    let suggestions = vec![(
+        commander.clone(),
+        HashMap::from([("Elf".to_owned(), vec![commander.clone()])]),
+    )];
+    let reports = commander_reports(&suggestions, &[(commander.clone(), 2)]);
+
+    let markdown = markdown_report(&reports);
+    assert!(markdown.contains(&format!(
+        "| Elf | [Abomination of Llanowar]({}) | Legendary Creature — Elf Horror | {{1}}{{B}}{{G}} | 2 |",
+        commander.scryfall_uri
+    )));
+
+    let csv = csv_report(&reports);
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("commander,commander_id,theme,card,card_id,oracle_id,type_line,mana_cost,synergy,owned")
+    );
+    assert_eq!(
+        lines.next(),
+        Some(
+            format!(
+                "Abomination of Llanowar,{0},Elf,Abomination of Llanowar,{0},{1},Legendary Creature — Elf Horror,{{1}}{{B}}{{G}},1,2",
+                commander.id, commander.oracle_id
+            )
+            .as_str()
+        )
+    );
+}
+
+#[test]
+fn config_profile_overrides_defaults() {
+    let config = parse_config(
+        r#"
+        database_uri = "mongodb://store:27017"
+        scryfall_url = "http://localhost:8080/"
+        output = "json"
+        house_bans = ["Sol Ring"]
+        budget = 100.0
+        theme_files = ["themes.toml"]
+        languages = ["en"]
+
+        [profiles.casual]
+        output = "markdown"
+        exclude = ["Armageddon"]
+        currency = "eur"
+        theme_files = ["casual-themes.toml"]
+        languages = ["de", "en"]
+        "#,
+    )
+    .unwrap();
+
+    let defaults = config.settings(None).unwrap();
+    assert_eq!(defaults.output.as_deref(), Some("json"));
+    assert_eq!(defaults.currency, None);
+
+    let casual = config.settings(Some("casual")).unwrap();
+    assert_eq!(casual.database_uri.as_deref(), Some("mongodb://store:27017"));
+    assert_eq!(casual.scryfall_url.as_deref(), Some("http://localhost:8080/"));
+    assert_eq!(casual.output.as_deref(), Some("markdown"));
+    assert_eq!(casual.budget, Some(100.0));
+    assert_eq!(casual.currency, Some(Currency::Eur));
+    assert_eq!(
+        casual.exclusions().unwrap().names,
+        ["Armageddon", "Sol Ring"]
+    );
+    assert_eq!(
+        casual.theme_files,
+        [PathBuf::from("themes.toml"), "casual-themes.toml".into()]
+    );
+    assert_eq!(casual.languages, ["de", "en"]);
+
+    assert!(config.settings(Some("cedh")).is_err());
+    assert!(parse_config("budjet = 5").is_err());
+}
+
+#[test]
+fn exclusions_by_name_oracle_id_and_query() {
+    let commander = load_card();
+    let mut time_warp = commander.clone();
+    time_warp.name = "Time Warp".to_owned();
+    time_warp.type_line = "Sorcery".to_owned();
+    time_warp.oracle_text = Some("Target player takes an extra turn after this one.".to_owned());
+    let mut game_changer = commander.clone();
+    game_changer.game_changer = true;
+
+    let query = |query: &str| query.parse::<Query>().unwrap();
+    assert!(query(r#"o:"extra turn""#).matches(&time_warp).unwrap());
+    assert!(query("t:sorcery").matches(&time_warp).unwrap());
+    assert!(!query("-t:sorcery").matches(&time_warp).unwrap());
+    assert!(query("kw:menace").matches(&commander).unwrap());
+    assert!(query("is:gamechanger").matches(&game_changer).unwrap());
+    assert!(query("Time Warp").matches(&time_warp).unwrap());
+    assert!(!query("o:extra turn").matches(&time_warp).unwrap());
+    assert!("is:broken".parse::<Query>().is_err());
+    assert!(parse_local_query("t:sorcery").is_ok());
+    assert!(parse_local_query("o:/extra turns?/").is_err());
+    let unsupported = parse_config("exclude_queries = ['t:land in:lea']").unwrap();
+    assert!(unsupported.settings.exclusions().is_err());
+
+    let by_query = Exclusions {
+        queries: vec![query(r#"o:"extra turn" or t:sorcery"#)],
+        ..Default::default()
+    };
+    assert!(by_query.excludes(&time_warp));
+    assert!(!by_query.excludes(&commander));
+    let by_oracle_id = Exclusions {
+        oracle_ids: vec![commander.oracle_id],
+        ..Default::default()
+    };
+    assert!(by_oracle_id.excludes(&commander));
+    let casual = Exclusions {
+        power_level: PowerLevel::Casual,
+        ..Default::default()
+    };
+    assert!(casual.excludes(&game_changer));
+    assert!(!casual.excludes(&commander));
+
+    let elf = elf();
+    let keywords = vec!["Elf".to_owned()];
+    let by_name = Exclusions {
+        names: vec![elf.name.clone()],
+        ..Default::default()
+    };
+    let analysis = analyze_deck_with_keywords(&commander, &keywords, &[], &[elf.clone()], &by_name);
+    assert!(analysis.missing_keyword_cards.is_empty());
+    let analysis = analyze_deck_with_keywords(&commander, &keywords, &[], &[elf], &by_oracle_id);
+    assert!(analysis.missing_keyword_cards.is_empty());
+}
+
+#[test]
+fn theme_rules_group_matching_cards() {
+    let rules = parse_theme_rules(
+        r#"
+        [[themes]]
+        name = "Elf tribal"
+        commander = "t:elf"
+        cards = "t:druid"
+
+        [[themes]]
+        name = "Sorceries"
+        cards = "t:sorcery"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(rules.len(), 2);
+    assert!(parse_theme_rules("[[themes]]\nname = \"Broken\"\ncards = \"is:broken\"").is_err());
+    assert!(parse_theme_rules("[[themes]]\nname = \"Reprints\"\ncards = \"in:lea\"").is_err());
+    assert!(parse_theme_rules("[[themes]]\nname = \"Typo\"\ncard = \"t:elf\"").is_err());
+
+    let commander = load_card();
+    let elf = elf();
+    let mut suggestions = vec![(commander.clone(), HashMap::new())];
+    add_themes(
+        &mut suggestions,
+        &rules,
+        &[commander.clone(), elf.clone()],
+        &Exclusions::default(),
+    );
+    let themes = &suggestions[0].1;
+    assert_eq!(themes.len(), 1);
+    assert_eq!(themes["Elf tribal"][0].name, elf.name);
+
+    let mut horror_commander = priced_card(5, "Horror Commander", Currency::Usd, "1.00");
+    horror_commander.type_line = "Legendary Creature — Horror".to_owned();
+    let mut mystic = elf.clone();
+    mystic.oracle_id = "00000000-0000-0000-0000-000000000006".parse().unwrap();
+    mystic.name = "Elvish Mystic".to_owned();
+    let mut suggestions = vec![
+        (
+            horror_commander,
+            HashMap::from([("Druid".to_owned(), vec![mystic.clone()])]),
+        ),
+        (
+            commander.clone(),
+            HashMap::from([("Elf".to_owned(), vec![elf.clone()])]),
+        ),
+    ];
+    add_themes(
+        &mut suggestions,
+        &rules,
+        &[elf.clone(), mystic.clone(), mystic],
+        &Exclusions::default(),
+    );
+    assert_eq!(suggestions[0].0.name, commander.name);
+    assert_eq!(suggestions[0].1["Elf tribal"].len(), 1);
+    assert_eq!(suggestions[0].1["Elf tribal"][0].name, "Elvish Mystic");
+    assert!(!suggestions[1].1.contains_key("Elf tribal"));
+
+    let mut suggestions = vec![(commander, HashMap::new())];
+    let by_name = Exclusions {
+        names: vec![elf.name.clone()],
+        ..Default::default()
+    };
+    add_themes(&mut suggestions, &rules, &[elf], &by_name);
+    assert!(suggestions[0].1.is_empty());
 }
 
 async fn setup_database() {
-    let file = File::open("tests/abomination-of-llanowar.json").unwrap();
-    let card: Card = serde_json::from_reader(file).unwrap();
+    let card = load_card();
     let collection = get_card_collection();
     collection.await.insert_one(card, None).await.unwrap();
 }
+
+fn load_card() -> Card {
+    let file = File::open("tests/abomination-of-llanowar.json").unwrap();
+    serde_json::from_reader(file).unwrap()
+}
+
+/// Valki, God of Lies // Tibalt, Cosmic Impostor, a modal double-faced card with a cost on each
+/// face and none of its own.
+fn load_mdfc() -> Card {
+    let file = File::open("tests/valki-god-of-lies.json").unwrap();
+    serde_json::from_reader(file).unwrap()
+}
+
+/// A creature sharing the Elf type with the commander of `load_card`, without any rules text of
+/// its own.
+fn elf() -> Card {
+    let mut elf = load_card();
+    elf.oracle_id = "00000000-0000-0000-0000-000000000001".parse().unwrap();
+    elf.name = "Llanowar Elves".to_owned();
+    elf.type_line = "Creature — Elf Druid".to_owned();
+    elf.oracle_text = Some(String::new());
+    elf
+}
+
+/// A copy of the `load_card` commander with its own oracle id, name and price in `currency`.
+fn priced_card(id: u8, name: &str, currency: Currency, price: &str) -> Card {
+    let mut card = load_card();
+    card.oracle_id = format!("00000000-0000-0000-0000-{id:012}").parse().unwrap();
+    card.name = name.to_owned();
+    let price = Some(price.to_owned());
+    match currency {
+        Currency::Usd => card.prices.usd = price,
+        Currency::Eur => card.prices.eur = price,
+        Currency::Tix => card.prices.tix = price,
+    }
+    card
+}
+
+fn land(template: &Card, name: &str, type_line: &str, produced_mana: Vec<Color>) -> Card {
+    let mut land = template.clone();
+    let id = name.bytes().map(u64::from).sum::<u64>();
+    land.oracle_id = format!("00000000-0000-0000-0000-{id:012}").parse().unwrap();
+    land.name = name.to_owned();
+    land.type_line = type_line.to_owned();
+    land.mana_cost = Some(String::new());
+    land.cmc = 0.0;
+    land.colors = Some(vec![]);
+    land.oracle_text = Some(format!(
+        "As {name} enters the battlefield, you may pay 2 life. If you don't, it enters the battlefield tapped."
+    ));
+    land.color_identity = produced_mana.clone();
+    land.produced_mana = Some(produced_mana);
+    land
+}